@@ -0,0 +1,344 @@
+use std::collections::HashMap;
+use std::process::ExitCode;
+use rand::Rng;
+use angler_bot::data::fish::FishRarity;
+use angler_bot::data::registry::GameData;
+use angler_bot::data::rng::{misc_rng, new_seed, GameRng};
+use angler_bot::data::rods::{BaseRod, Rod};
+use angler_bot::data::shop::RodRarity;
+use angler_bot::data::userfile::{UserValues, DEFAULT_ROD};
+use angler_bot::engine::cast::roll_cast;
+use angler_bot::engine::catch::{resolve_catch, CatchOutcome};
+use angler_bot::{nay, say, yay};
+
+// Monte Carlo economy simulator for balancing rods and fish.
+// Run from the repository root: `cargo run --release --bin angler-simulate -- [options]`
+//
+//   --casts <n>         casts simulated per rod for the earnings table (default 20000)
+//   --runs <n>          progressions simulated per shop rod (default 200)
+//   --recast <seconds>  time a player takes between casts (default 5)
+//   --max-hours <h>     give up on a progression after this much fishing (default 1000)
+//   --csv <dir>         where the CSV reports are written (default ./simulation)
+//   --seed <n>          seed for every roll, so a run can be repeated exactly (default random)
+
+const RARITIES: [FishRarity; 6] = [FishRarity::Common, FishRarity::Uncommon, FishRarity::Rare,
+    FishRarity::Elusive, FishRarity::Legendary, FishRarity::Mythical];
+
+struct Options {
+    casts: u32,
+    runs: u32,
+    recast: f64,
+    max_hours: f64,
+    csv_dir: String,
+    seed: u64,
+}
+
+impl Options {
+    fn parse() -> Result<Self, String> {
+        let mut options = Self {
+            casts: 20000,
+            runs: 200,
+            recast: 5.0,
+            max_hours: 1000.0,
+            csv_dir: "./simulation".to_string(),
+            seed: new_seed(),
+        };
+
+        let mut args = std::env::args().skip(1);
+        while let Some(flag) = args.next() {
+            let value = args.next().ok_or(format!("{} needs a value", flag))?;
+            let invalid = format!("invalid value for {}: {}", flag, value);
+            match flag.as_str() {
+                "--casts" => options.casts = value.parse().map_err(|_| invalid)?,
+                "--runs" => options.runs = value.parse().map_err(|_| invalid)?,
+                "--recast" => options.recast = value.parse().map_err(|_| invalid)?,
+                "--max-hours" => options.max_hours = value.parse().map_err(|_| invalid)?,
+                "--csv" => options.csv_dir = value,
+                "--seed" => options.seed = value.parse().map_err(|_| invalid)?,
+                _ => return Err(format!("unknown option {}", flag)),
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+/// One `/fish` cast, rolled and resolved by the engine exactly like the bot does it.
+/// Returns the seconds the cast took and what happened.
+/// Every cast starts with an empty cooler, so fish are treated as sold fresh,
+/// and is made at the deepest location the rod can fish, in the weather and time of day of a random moment,
+/// with the events a guild gets by default.
+fn simulate_cast(rod: &Rod, game_data: &GameData, rng: &mut GameRng) -> (f64, CatchOutcome) {
+    let location = game_data.locations.deepest_for(rod.get_depth());
+    let conditions = game_data.weather.conditions(0, 0, rng.gen_range(0..i32::MAX as i64));
+    let cast = roll_cast(rod, None, location, &conditions, game_data, rng.gen());
+    let events = game_data.events.enabled(&HashMap::new());
+    let outcome = resolve_catch(&mut UserValues::default(), &cast, game_data, &events, 0);
+
+    (cast.catch_time as f64 / 1000.0, outcome)
+}
+
+#[derive(Default)]
+struct RodStats {
+    casts: u64,
+    seconds: f64,
+    money: u64,
+    escaped: u64,
+    line_broke: u64,
+    stolen: u64,
+    rarities: [u64; 6],
+}
+
+impl RodStats {
+    fn caught(&self) -> u64 {
+        self.rarities.iter().sum()
+    }
+
+    fn per_hour(&self) -> f64 {
+        if self.seconds == 0.0 {
+            return 0.0;
+        }
+        self.money as f64 / self.seconds * 3600.0
+    }
+
+    fn percent(&self, count: u64, of: u64) -> f64 {
+        if of == 0 {
+            return 0.0;
+        }
+        count as f64 / of as f64 * 100.0
+    }
+}
+
+fn simulate_rod(base: &BaseRod, game_data: &GameData, options: &Options, rng: &mut GameRng) -> RodStats {
+    let rod = Rod { base: base.clone(), modifier: None };
+    let mut stats = RodStats::default();
+
+    for _ in 0..options.casts {
+        let (seconds, result) = simulate_cast(&rod, game_data, rng);
+        stats.casts += 1;
+        stats.seconds += seconds + options.recast;
+
+        match result {
+            CatchOutcome::Caught { fish, value, .. } => {
+                stats.money += value as u64;
+                stats.rarities[fish.rarity.ident() as usize] += 1;
+            }
+            CatchOutcome::Escaped { .. } => stats.escaped += 1,
+            CatchOutcome::LineBroke { .. } => stats.line_broke += 1,
+            CatchOutcome::Stolen { .. } => stats.stolen += 1,
+            CatchOutcome::CoolerFull { .. } => {}
+        }
+    }
+
+    stats
+}
+
+/// Hours of fishing until `target` is affordable and unlocked, or `None` if the run gave up.
+/// With `upgrade` the player buys any cheaper unlocked rod that earns more than their current one along the way.
+fn simulate_progression(target: &BaseRod, shop_rods: &[&BaseRod], per_hour: &HashMap<String, f64>,
+                        upgrade: bool, game_data: &GameData, options: &Options, rng: &mut GameRng) -> Option<f64> {
+    let starter = game_data.rods.get_base_by_name(DEFAULT_ROD)?;
+    let target_cost = target.get_cost(&game_data.multipliers);
+
+    let mut rod = Rod { base: starter, modifier: None };
+    let mut rate = per_hour.get(&rod.base.name).copied().unwrap_or(0.0);
+    let leveling = &game_data.config.leveling;
+    let mut money = 0u32;
+    let mut xp = 0u32;
+    let mut seconds = 0.0;
+
+    while money < target_cost || leveling.level(xp) < target.required_level {
+        if seconds / 3600.0 >= options.max_hours {
+            return None;
+        }
+
+        let (cast_seconds, result) = simulate_cast(&rod, game_data, rng);
+        seconds += cast_seconds + options.recast;
+        if let CatchOutcome::Caught { value, xp: gained, .. } = result {
+            money += value;
+            xp = xp.saturating_add(gained);
+        }
+
+        if !upgrade {
+            continue;
+        }
+
+        let best_upgrade = shop_rods.iter()
+            .filter(|base| base.name != target.name)
+            .filter(|base| base.get_cost(&game_data.multipliers) <= money)
+            .filter(|base| base.get_cost(&game_data.multipliers) < target_cost)
+            .filter(|base| base.required_level <= leveling.level(xp))
+            .filter(|base| per_hour.get(&base.name).copied().unwrap_or(0.0) > rate)
+            .max_by(|a, b| per_hour[&a.name].total_cmp(&per_hour[&b.name]));
+
+        if let Some(base) = best_upgrade {
+            money -= base.get_cost(&game_data.multipliers);
+            rate = per_hour[&base.name];
+            rod = Rod { base: (*base).clone(), modifier: game_data.rods.generate_modifier(rng) };
+        }
+    }
+
+    Some(seconds / 3600.0)
+}
+
+struct ProgressionStats {
+    median: Option<f64>,
+    mean: Option<f64>,
+    p90: Option<f64>,
+    gave_up: u32,
+}
+
+fn summarize(mut hours: Vec<f64>, runs: u32) -> ProgressionStats {
+    hours.sort_by(|a, b| a.total_cmp(b));
+    let gave_up = runs - hours.len() as u32;
+
+    if hours.is_empty() {
+        return ProgressionStats { median: None, mean: None, p90: None, gave_up };
+    }
+
+    ProgressionStats {
+        median: Some(hours[hours.len() / 2]),
+        mean: Some(hours.iter().sum::<f64>() / hours.len() as f64),
+        p90: Some(hours[(hours.len() * 9 / 10).min(hours.len() - 1)]),
+        gave_up,
+    }
+}
+
+fn hours(value: Option<f64>) -> String {
+    value.map(|h| format!("{:.1}", h)).unwrap_or("-".to_string())
+}
+
+fn print_table(headers: &[&str], rows: &[Vec<String>]) {
+    let widths: Vec<usize> = headers.iter().enumerate()
+        .map(|(i, header)| rows.iter().map(|row| row[i].len()).max().unwrap_or(0).max(header.len()))
+        .collect();
+
+    let line = |cells: Vec<String>| cells.iter().enumerate()
+        .map(|(i, cell)| if i == 0 { format!("{:<w$}", cell, w = widths[i]) } else { format!("{:>w$}", cell, w = widths[i]) })
+        .collect::<Vec<String>>()
+        .join("  ");
+
+    println!("{}", line(headers.iter().map(|h| h.to_string()).collect()));
+    println!("{}", widths.iter().map(|w| "-".repeat(*w)).collect::<Vec<String>>().join("  "));
+    for row in rows {
+        println!("{}", line(row.clone()));
+    }
+    println!();
+}
+
+fn write_csv(dir: &str, name: &str, headers: &[&str], rows: &[Vec<String>]) {
+    let escape = |cell: &String| if cell.contains(',') { format!("\"{}\"", cell) } else { cell.clone() };
+
+    let mut contents = headers.join(",");
+    contents.push('\n');
+    for row in rows {
+        contents.push_str(row.iter().map(escape).collect::<Vec<String>>().join(",").as_str());
+        contents.push('\n');
+    }
+
+    let path = format!("{}/{}", dir, name);
+    if let Err(e) = std::fs::write(path.as_str(), contents) {
+        nay!("Failed to write {}: {}", path, e);
+    }
+}
+
+fn main() -> ExitCode {
+    let options = match Options::parse() {
+        Ok(options) => options,
+        Err(e) => {
+            nay!("{}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let game_data = match GameData::load() {
+        Ok(game_data) => game_data,
+        Err(e) => {
+            nay!("Game data is invalid, run angler-validate for details: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut rng = misc_rng(options.seed);
+
+    // earnings per rod
+    say!("Simulating {} casts for each of {} rods (seed: {})", options.casts, game_data.rods.rods.len(), options.seed);
+
+    let rod_stats: Vec<(&BaseRod, RodStats)> = game_data.rods.rods.iter()
+        .map(|base| (base, simulate_rod(base, &game_data, &options, &mut rng)))
+        .collect();
+
+    let per_hour: HashMap<String, f64> = rod_stats.iter()
+        .map(|(base, stats)| (base.name.clone(), stats.per_hour()))
+        .collect();
+
+    let earnings_headers = ["Rod", "Rarity", "Cost", "$/hour", "Casts/hour", "Caught %", "Escaped %", "Line broke %", "Stolen %"];
+    let earnings_rows: Vec<Vec<String>> = rod_stats.iter().map(|(base, stats)| vec![
+        base.name.clone(),
+        base.rarity.clone(),
+        base.get_cost(&game_data.multipliers).to_string(),
+        format!("{:.0}", stats.per_hour()),
+        format!("{:.1}", stats.casts as f64 / stats.seconds * 3600.0),
+        format!("{:.1}", stats.percent(stats.caught(), stats.casts)),
+        format!("{:.1}", stats.percent(stats.escaped, stats.casts)),
+        format!("{:.1}", stats.percent(stats.line_broke, stats.casts)),
+        format!("{:.1}", stats.percent(stats.stolen, stats.casts)),
+    ]).collect();
+
+    let mut rarity_headers = vec!["Rod"];
+    let rarity_names: Vec<String> = RARITIES.iter().map(|rarity| format!("{} %", rarity)).collect();
+    rarity_headers.extend(rarity_names.iter().map(|name| name.as_str()));
+    let rarity_rows: Vec<Vec<String>> = rod_stats.iter().map(|(base, stats)| {
+        let mut row = vec![base.name.clone()];
+        row.extend(stats.rarities.iter().map(|count| format!("{:.2}", stats.percent(*count, stats.caught()))));
+        row
+    }).collect();
+
+    // time to afford each shop rod
+    let mut shop_rods: Vec<&BaseRod> = game_data.rods.rods.iter()
+        .filter(|base| !matches!(base.get_rarity(), RodRarity::Unobtainable))
+        .collect();
+    shop_rods.sort_by_key(|base| base.get_cost(&game_data.multipliers));
+
+    say!("Simulating {} progressions for each of {} shop rods", options.runs, shop_rods.len());
+
+    let progression_headers = ["Rod", "Cost", "Starter only (median h)", "Starter only (mean h)", "Starter only (p90 h)",
+        "With upgrades (median h)", "With upgrades (mean h)", "With upgrades (p90 h)", "Gave up"];
+    let progression_rows: Vec<Vec<String>> = shop_rods.iter().map(|target| {
+        let mut run = |upgrade: bool| {
+            let hours = (0..options.runs)
+                .filter_map(|_| simulate_progression(target, &shop_rods, &per_hour, upgrade, &game_data, &options, &mut rng))
+                .collect();
+            summarize(hours, options.runs)
+        };
+        let starter = run(false);
+        let upgrades = run(true);
+
+        vec![
+            target.name.clone(),
+            target.get_cost(&game_data.multipliers).to_string(),
+            hours(starter.median), hours(starter.mean), hours(starter.p90),
+            hours(upgrades.median), hours(upgrades.mean), hours(upgrades.p90),
+            (starter.gave_up + upgrades.gave_up).to_string(),
+        ]
+    }).collect();
+
+    println!();
+    println!("Earnings per rod (no modifier, {}s between casts)", options.recast);
+    print_table(&earnings_headers, &earnings_rows);
+    println!("Rarity of landed fish per rod");
+    print_table(&rarity_headers, &rarity_rows);
+    println!("Hours of fishing from a new profile until each shop rod is affordable and unlocked");
+    print_table(&progression_headers, &progression_rows);
+
+    if let Err(e) = std::fs::create_dir_all(options.csv_dir.as_str()) {
+        nay!("Failed to create {}: {}", options.csv_dir, e);
+        return ExitCode::FAILURE;
+    }
+    write_csv(options.csv_dir.as_str(), "earnings.csv", &earnings_headers, &earnings_rows);
+    write_csv(options.csv_dir.as_str(), "rarities.csv", &rarity_headers, &rarity_rows);
+    write_csv(options.csv_dir.as_str(), "progression.csv", &progression_headers, &progression_rows);
+
+    yay!("Wrote CSV reports to {}", options.csv_dir);
+    ExitCode::SUCCESS
+}
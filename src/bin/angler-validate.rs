@@ -0,0 +1,55 @@
+use std::process::ExitCode;
+use angler_bot::data::registry::GameData;
+use angler_bot::data::validate::Severity;
+use angler_bot::error::AnglerError;
+use angler_bot::{hey, nay, say, yay};
+
+// Checks data/config.json, data/fish.json, data/rods.json, data/multipliers.json, data/bait.json, data/achievements.json,
+// data/locations.json, data/weather.json and data/events.json for mistakes before they reach the bot.
+// Runs the same checks the bot does when it loads or reloads the data.
+// Run from the repository root: `cargo run --bin angler-validate`
+
+fn main() -> ExitCode {
+    say!("Validating game data");
+
+    let game_data = match GameData::read() {
+        Ok(game_data) => game_data,
+        Err(AnglerError::InvalidData(errors)) => {
+            for error in errors {
+                nay!("{}", error);
+            }
+            return ExitCode::FAILURE;
+        }
+        Err(e) => {
+            nay!("{}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut errors = 0;
+    let mut warnings = 0;
+    for issue in game_data.issues() {
+        match issue.severity {
+            Severity::Error => {
+                errors += 1;
+                nay!("{}", issue);
+            }
+            Severity::Warning => {
+                warnings += 1;
+                hey!("{}", issue);
+            }
+        }
+    }
+
+    if errors > 0 {
+        nay!("Found {} errors and {} warnings in {} fish and {} rods",
+            errors, warnings, game_data.fish.fish.len(), game_data.rods.rods.len());
+        return ExitCode::FAILURE;
+    }
+
+    yay!("Game data is valid ({} warnings) - {} fish, {} rods, {} modifiers, {} bait, {} achievements, {} locations, {} weather, {} events",
+        warnings, game_data.fish.fish.len(), game_data.rods.rods.len(), game_data.rods.modifiers.len(),
+        game_data.bait.bait.len(), game_data.achievements.achievements.len(), game_data.locations.locations.len(),
+        game_data.weather.weather.len(), game_data.events.events.len());
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,64 @@
+use serenity::all::{Colour, CommandInteraction, Context, CreateCommand, CreateEmbed, CreateEmbedFooter,
+                    CreateInteractionResponse, CreateInteractionResponseMessage, GuildId, User};
+use crate::commands::asset;
+use crate::data::achievements::Achievement;
+use crate::data::registry::GameData;
+use crate::data::userfile::store;
+use crate::error::AnglerError;
+
+/// Discord only shows 25 fields in an embed
+const MAX_FIELDS: usize = 25;
+
+/// One line per newly unlocked achievement, to tack onto whatever message unlocked them
+pub fn announcement(unlocked: &[Achievement]) -> String {
+    unlocked.iter()
+        .map(|achievement| format!("\n:trophy: Achievement unlocked: **{}**! Reward: {}", achievement.name, achievement.reward))
+        .collect()
+}
+
+pub async fn run(ctx: &Context, cmd: &CommandInteraction, sender: &User, guild: &GuildId,
+                 game_data: &GameData) -> Result<(), AnglerError> {
+    let user_data = store().read(&sender.id, guild.get());
+    let achievements = &game_data.achievements.achievements;
+
+    let fields = achievements.iter().map(|achievement| {
+        if user_data.achievements.contains(&achievement.id) {
+            (format!(":trophy: {}", achievement.name),
+             format!("{}\n**Unlocked!**", achievement.description), true)
+        } else {
+            let (progress, goal) = achievement.progress(&user_data, game_data);
+            (achievement.name.clone(),
+             format!("{}\nProgress: {}/{}\nReward: {}", achievement.description, progress.min(goal), goal, achievement.reward),
+             true)
+        }
+    }).collect::<Vec<(String, String, bool)>>();
+
+    let unlocked = achievements.iter().filter(|achievement| user_data.achievements.contains(&achievement.id)).count();
+
+    // create the embedded message
+    let embed = CreateEmbed::new()
+        .title(format!("{}'s Achievements", sender.global_name.clone().unwrap_or(sender.name.clone())))
+        .thumbnail("attachment://rod_with_fish.png")
+        .description(match &user_data.title {
+            Some(title) => format!("Title: **{}**", title),
+            None => "Unlock achievements to earn money and titles!".to_string(),
+        })
+        .fields(fields.into_iter().take(MAX_FIELDS))
+        .footer(CreateEmbedFooter::new(format!("{}/{} unlocked", unlocked, achievements.len())))
+        .color(Colour::GOLD);
+
+    // create the message builder
+    let builder = CreateInteractionResponse::Message(CreateInteractionResponseMessage::new()
+        .embed(embed)
+        .add_files(asset("rod_with_fish.png").await));
+
+    // send the message
+    cmd.create_response(&ctx.http, builder).await?;
+    Ok(())
+}
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("achievements")
+        .description("View your achievements and your progress towards the rest")
+        .dm_permission(false)
+}
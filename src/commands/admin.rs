@@ -0,0 +1,182 @@
+use serenity::all::{CommandInteraction, CommandOptionType, Context, CreateCommand, CreateCommandOption,
+                    CreateInteractionResponse, CreateInteractionResponseMessage, Permissions, ResolvedOption,
+                    ResolvedValue};
+use crate::commands::tournament;
+use crate::data::events::EventData;
+use crate::data::guildfile::modify_guild;
+use crate::data::registry;
+use crate::data::registry::game_data;
+use crate::data::tournament::Scoring;
+use crate::data::userfile::store;
+use crate::error::AnglerError;
+use crate::{nay, register_data_commands, yay, SKEPZ_ID};
+
+/// Whether the sender may run admin commands in this guild
+pub fn is_admin(cmd: &CommandInteraction) -> bool {
+    if cmd.user.id == SKEPZ_ID {
+        return true;
+    }
+
+    cmd.member.as_ref()
+        .and_then(|member| member.permissions)
+        .map(|permissions| permissions.administrator())
+        .unwrap_or(false)
+}
+
+/// Reloads the data files every guild plays with, so only the bot owner may do it
+async fn reload(ctx: &Context, cmd: &CommandInteraction) -> String {
+    if cmd.user.id != SKEPZ_ID {
+        return "The game data is shared by every server, so only the bot owner can reload it!".to_string();
+    }
+
+    match registry::reload(ctx).await {
+        Ok(()) => {
+            // the bait, event and location choices were built from the old data
+            register_data_commands(ctx).await;
+            yay!("Game data reloaded");
+            "Game data reloaded!".to_string()
+        }
+        Err(AnglerError::InvalidData(errors)) => {
+            nay!("Rejected game data reload:\n{}", errors.join("\n"));
+            format!("The new game data is invalid, keeping the current data:\n- {}", errors.join("\n- "))
+        }
+        Err(e) => {
+            nay!("Rejected game data reload: {}", e);
+            format!("The new game data couldn't be loaded, keeping the current data:\n- {}", e)
+        }
+    }
+}
+
+/// Sets the UTC offset the guild's day and night follow
+fn set_timezone(options: &[ResolvedOption<'_>], cmd: &CommandInteraction) -> String {
+    let Some(guild_id) = cmd.guild_id else {
+        return "Timezones can only be set in a server!".to_string();
+    };
+    let hours = options.iter().find_map(|option| match option.value {
+        ResolvedValue::Number(hours) if option.name == "offset" => Some(hours),
+        _ => None,
+    }).unwrap_or(0.0);
+
+    let utc_offset = (hours * 60.0).round() as i32;
+    modify_guild(guild_id.get(), |guild| guild.utc_offset = utc_offset);
+    yay!("{} set the timezone of guild {} to UTC{:+}", cmd.user.name, guild_id, hours);
+
+    format!("The server's clock is now UTC{:+}. Check the time of day with `/forecast`.", hours)
+}
+
+/// Every event and whether it can happen in the guild
+async fn list_events(ctx: &Context, cmd: &CommandInteraction) -> String {
+    let Some(guild_id) = cmd.guild_id else {
+        return "Events can only be listed in a server!".to_string();
+    };
+    let game_data = game_data(ctx).await;
+    let overrides = store().read_guild(guild_id.get()).events;
+
+    let lines = game_data.events.events.iter().map(|event| {
+        let enabled = overrides.get(&event.id).copied().unwrap_or(event.enabled);
+        format!("- **{}** (`{}`): {}, {}% chance", event.name, event.id, if enabled { "on" } else { "off" }, event.chance)
+    }).collect::<Vec<String>>();
+
+    format!("Random events in this server:\n{}\nSwitch them with `/admin event`.", lines.join("\n"))
+}
+
+/// Switches one of the random events on or off for the guild
+async fn set_event(options: &[ResolvedOption<'_>], ctx: &Context, cmd: &CommandInteraction) -> String {
+    let Some(guild_id) = cmd.guild_id else {
+        return "Events can only be changed in a server!".to_string();
+    };
+    let (mut id, mut enabled) = (None, true);
+    for option in options {
+        match option.value {
+            ResolvedValue::String(value) if option.name == "id" => id = Some(value),
+            ResolvedValue::Boolean(value) if option.name == "enabled" => enabled = value,
+            _ => {}
+        }
+    }
+
+    let game_data = game_data(ctx).await;
+    let Some(event) = id.and_then(|id| game_data.events.get_by_id(id)) else {
+        return "Unknown event! See them all with `/admin events`.".to_string();
+    };
+
+    modify_guild(guild_id.get(), |guild| guild.events.insert(event.id.clone(), enabled));
+    yay!("{} turned the {} event {} in guild {}", cmd.user.name, event.id, if enabled { "on" } else { "off" }, guild_id);
+
+    format!("**{}** is now {} in this server.", event.name, if enabled { "on" } else { "off" })
+}
+
+pub async fn run(options: &[ResolvedOption<'_>], ctx: &Context, cmd: &CommandInteraction) -> Result<(), AnglerError> {
+    let content = if !is_admin(cmd) {
+        "You must be a server administrator to do this!".to_string()
+    } else {
+        match options.first() {
+            Some(ResolvedOption { name: "reload", value: ResolvedValue::SubCommand(_), .. }) => reload(ctx, cmd).await,
+            Some(ResolvedOption { name: "tournament", value: ResolvedValue::SubCommand(options), .. }) =>
+                tournament::start(options, ctx, cmd).await?,
+            Some(ResolvedOption { name: "endtournament", value: ResolvedValue::SubCommand(_), .. }) =>
+                tournament::end(ctx, cmd).await?,
+            Some(ResolvedOption { name: "timezone", value: ResolvedValue::SubCommand(options), .. }) =>
+                set_timezone(options, cmd),
+            Some(ResolvedOption { name: "events", value: ResolvedValue::SubCommand(_), .. }) =>
+                list_events(ctx, cmd).await,
+            Some(ResolvedOption { name: "event", value: ResolvedValue::SubCommand(options), .. }) =>
+                set_event(options, ctx, cmd).await,
+            _ => "Unknown admin command".to_string(),
+        }
+    };
+
+    let builder = CreateInteractionResponse::Message(CreateInteractionResponseMessage::new()
+        .content(content)
+        .ephemeral(true));
+
+    cmd.create_response(&ctx.http, builder).await?;
+    Ok(())
+}
+
+pub fn register(event_data: &EventData) -> CreateCommand {
+    let mut event = CreateCommandOption::new(CommandOptionType::String, "id", "The event to switch")
+        .required(true);
+    for random_event in &event_data.events {
+        event = event.add_string_choice(random_event.name.as_str(), random_event.id.as_str());
+    }
+
+    let mut scoring = CreateCommandOption::new(CommandOptionType::String, "scoring", "How entries are ranked")
+        .required(true);
+    for mode in Scoring::ALL {
+        scoring = scoring.add_string_choice(mode.to_string(), mode.id());
+    }
+
+    CreateCommand::new("admin")
+        .description("Angler Bot administration")
+        .default_member_permissions(Permissions::ADMINISTRATOR)
+        .dm_permission(false)
+        .add_option(CreateCommandOption::new(CommandOptionType::SubCommand, "reload",
+                                             "Reload the game data files for every server (bot owner only)"))
+        .add_option(CreateCommandOption::new(CommandOptionType::SubCommand, "tournament",
+                                             "Start a fishing tournament in this channel")
+            .add_sub_option(CreateCommandOption::new(CommandOptionType::Integer, "minutes", "How long the tournament lasts")
+                .min_int_value(1)
+                .max_int_value(10080)
+                .required(true))
+            .add_sub_option(scoring)
+            .add_sub_option(CreateCommandOption::new(CommandOptionType::String, "species", "Only count this kind of fish"))
+            .add_sub_option(CreateCommandOption::new(CommandOptionType::Integer, "prize",
+                                                     "The prize pool split between the top three, $1000 by default")
+                .min_int_value(0)
+                .max_int_value(tournament::MAX_PRIZE_POOL as u64)))
+        .add_option(CreateCommandOption::new(CommandOptionType::SubCommand, "endtournament",
+                                             "End the running tournament now and pay out the prizes"))
+        .add_option(CreateCommandOption::new(CommandOptionType::SubCommand, "timezone",
+                                             "Set the server's timezone, which decides when it's day or night")
+            .add_sub_option(CreateCommandOption::new(CommandOptionType::Number, "offset", "Hours ahead of UTC, e.g. -5 or 5.5")
+                .min_number_value(-12.0)
+                .max_number_value(14.0)
+                .required(true)))
+        .add_option(CreateCommandOption::new(CommandOptionType::SubCommand, "events",
+                                             "List the random events and whether they happen in this server"))
+        .add_option(CreateCommandOption::new(CommandOptionType::SubCommand, "event",
+                                             "Switch a random event on or off for this server")
+            .add_sub_option(event)
+            .add_sub_option(CreateCommandOption::new(CommandOptionType::Boolean, "enabled", "Whether the event can happen")
+                .required(true)))
+}
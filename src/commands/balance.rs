@@ -1,8 +1,8 @@
 use serenity::all::{CreateCommand, GuildId, UserId};
-use crate::data::userfile::read_userfile;
+use crate::data::userfile::store;
 
 pub fn run(sender: &UserId, guild: &GuildId) -> String {
-    let user_data = read_userfile(sender, guild.get());
+    let user_data = store().read(sender, guild.get());
     format!("You have ${}", user_data.money)
 }
 
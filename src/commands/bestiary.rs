@@ -1,21 +1,39 @@
-use serenity::all::{Colour, CommandInteraction, Context, CreateAttachment, CreateEmbed,
+use serenity::all::{Colour, CommandInteraction, Context, CreateEmbed,
                     CreateEmbedFooter, CreateInteractionResponse, CreateInteractionResponseMessage,
                     GuildId, User};
 use serenity::builder::CreateCommand;
-use crate::data::fish::FishData;
-use crate::data::userfile::read_userfile;
-use crate::nay;
+use crate::data::registry::GameData;
+use crate::commands::asset;
+use crate::data::userfile::store;
+use crate::error::AnglerError;
+use crate::hey;
 
-pub async fn run(ctx: &Context, cmd: &CommandInteraction, sender: &User, guild: &GuildId) {
-    let user_data = read_userfile(&sender.id, guild.get());
+pub async fn run(ctx: &Context, cmd: &CommandInteraction, sender: &User, guild: &GuildId,
+                 game_data: &GameData) -> Result<(), AnglerError> {
+    let user_data = store().read(&sender.id, guild.get());
 
-    let fish_data = FishData::load();
+    let fish_data = &game_data.fish;
+    let today = game_data.weather.current(guild.get()).date;
 
-    let caught_fish_info = user_data.has_seen.iter().map(|name| {
-        let fish = fish_data.fish_type_by_name(name).unwrap();
-        (name.clone(),
-         format!("Can be found between {}lbs to {}lbs below {}ft", fish.min_weight, fish.max_weight, fish.depth),
-         false)
+    // species removed from fish.json since they were caught are skipped rather than failing the whole page
+    let caught_fish_info = user_data.has_seen.iter().filter_map(|name| {
+        let Some(fish) = fish_data.fish_type_by_name(name) else {
+            hey!("{} has seen {}, which is no longer in fish.json", sender.name, name);
+            return None;
+        };
+        let habitats = if fish.habitats.is_empty() { "Nowhere".to_string() } else { fish.habitats.join(", ") };
+        let mut info = format!("Can be found between {}lbs to {}lbs below {}ft\nLives in: {}",
+                               fish.min_weight, fish.max_weight, fish.depth, habitats);
+        if fish.is_seasonal() {
+            let windows = fish.active.iter().map(|window| window.to_string()).collect::<Vec<String>>().join(", ");
+            let status = match fish.returns_on(today) {
+                _ if fish.is_active(today) => "in season now".to_string(),
+                Some(date) => format!("returns {}", date.format("%b %-d, %Y")),
+                None => "gone for good".to_string(),
+            };
+            info.push_str(format!("\n:calendar: Seasonal ({}), {}", windows, status).as_str());
+        }
+        Some((name.clone(), info, false))
     }).collect::<Vec<(String, String, bool)>>();
 
     // create the embedded message
@@ -30,12 +48,11 @@ pub async fn run(ctx: &Context, cmd: &CommandInteraction, sender: &User, guild:
     // create the message builder
     let builder = CreateInteractionResponse::Message(CreateInteractionResponseMessage::new()
         .embed(embed)
-        .add_file(CreateAttachment::path("./assets/rod_with_fish.png").await.unwrap()));
+        .add_files(asset("rod_with_fish.png").await));
 
     // send the message
-    if let Err(err) = cmd.create_response(&ctx.http, builder).await {
-        nay!("Failed to respond to command: {}", err)
-    }
+    cmd.create_response(&ctx.http, builder).await?;
+    Ok(())
 }
 
 pub fn register() -> CreateCommand {
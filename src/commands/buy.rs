@@ -1,14 +1,17 @@
 use serenity::all::{CommandOptionType, CreateCommand, CreateCommandOption, GuildId, ResolvedOption, ResolvedValue, User};
+use crate::commands::achievements::announcement;
+use crate::data::registry::GameData;
+use crate::data::rng::{misc_rng, new_seed};
+use crate::data::userfile::modify_user;
+use crate::data::shop::{BAIT_STOCK, ROD_STOCK};
+use crate::engine::achievements::check_achievements;
+use crate::engine::buy::{buy_bait, buy_rod, BaitPurchase, BuyError, Purchase};
+use crate::error::AnglerError;
+use crate::say;
 
-pub fn run(options: &[ResolvedOption], sender: &User, guild: &GuildId) -> String {
+pub fn run(options: &[ResolvedOption], sender: &User, guild: &GuildId, game_data: &GameData) -> Result<String, AnglerError> {
     // get the current shop
-    let mut shop = crate::data::shop::Shop::load();
-
-    let user_file = crate::data::userfile::read_userfile(&sender.id, guild.get());
-
-    if user_file.cast {
-        return "Please wait until your cast is finished to buy a new rod!".to_string();
-    }
+    let shop = crate::data::shop::Shop::load(&game_data.rods, &game_data.bait)?;
 
     // get the index of the item to buy
     if let Some(ResolvedOption {
@@ -16,18 +19,44 @@ pub fn run(options: &[ResolvedOption], sender: &User, guild: &GuildId) -> String
                 }) = options.first() {
         let item_index = *item_index as usize - 1;
 
-        let buy_result = shop.sell_rod(item_index, guild.get(), &sender.id);
+        // the slots after the rods are bait
+        if item_index >= ROD_STOCK {
+            let bait_result = modify_user(guild.get(), &sender.id, |user| {
+                buy_bait(user, &shop, item_index - ROD_STOCK, game_data)
+            });
 
-        if let Err(e) = buy_result {
-            return match e {
-                crate::data::shop::BuyError::InvalidRod => "Invalid item!".to_string(),
-                crate::data::shop::BuyError::NoMoney => "You don't have enough money!".to_string(),
-            };
+            return Ok(match bait_result {
+                Ok(BaitPurchase { bait, cost }) => {
+                    say!("{} bought {} {} for ${}", sender.name, bait.quantity, bait, cost);
+                    format!("You bought {} {}! Use it with `/fish bait:{}`.", bait.quantity, bait, bait)
+                }
+                Err(BuyError::NoMoney) => "You don't have enough money!".to_string(),
+                Err(e) => e.to_string(),
+            });
         }
 
-        buy_result.unwrap_or("Invalid item!".to_string())
+        let seed = new_seed();
+        let buy_result = modify_user(guild.get(), &sender.id, |user| {
+            buy_rod(user, &shop, item_index, game_data, &mut misc_rng(seed))
+                .map(|purchase| (purchase, check_achievements(user, game_data)))
+        });
+
+        Ok(match buy_result {
+            Ok((Purchase { rod, cost }, unlocked)) => {
+                say!("{} bought a {} for ${} (seed: {})", sender.name, rod, cost, seed);
+                let bought = if let Some(m) = &rod.modifier {
+                    format!("You now own a {}!\n  Your rod has a modifier: {}!", rod, m.name)
+                } else {
+                    format!("You now own a {}!", rod)
+                };
+                format!("{}\n  It's been equipped, your other rods are in your `/inventory`.{}", bought, announcement(&unlocked))
+            }
+            Err(BuyError::InvalidRod) => "Invalid item!".to_string(),
+            Err(BuyError::NoMoney) => "You don't have enough money!".to_string(),
+            Err(e) => e.to_string(),
+        })
     } else {
-        "Invalid item!".to_string()
+        Ok("Invalid item!".to_string())
     }
 }
 
@@ -38,6 +67,6 @@ pub fn register() -> CreateCommand {
         .add_option(CreateCommandOption::new(CommandOptionType::Integer, "item",
                                              "The item to buy from the shop")
             .min_int_value(1)
-            .max_int_value(6)
+            .max_int_value((ROD_STOCK + BAIT_STOCK) as u64)
             .required(true))
-}
\ No newline at end of file
+}
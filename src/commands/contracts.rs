@@ -0,0 +1,78 @@
+use serenity::all::{Colour, CommandInteraction, CommandOptionType, Context, CreateCommand, CreateCommandOption, CreateEmbed,
+                    CreateEmbedFooter, CreateInteractionResponse, CreateInteractionResponseMessage, GuildId,
+                    ResolvedOption, ResolvedValue, User};
+use crate::commands::asset;
+use crate::data::contracts::{Contract, ContractBoard};
+use crate::data::registry::GameData;
+use crate::data::shop::Shop;
+use crate::data::userfile::modify_user;
+use crate::engine::contracts::{claim_contracts, Claim};
+use crate::error::AnglerError;
+use crate::say;
+
+/// One line per contract an action just finished, to tack onto its message
+pub fn finished_note(finished: &[Contract]) -> String {
+    finished.iter()
+        .map(|contract| format!("\n:scroll: Contract complete: **{}**! Claim ${} with `/contracts claim:True`.", contract, contract.reward))
+        .collect()
+}
+
+pub async fn run(options: &[ResolvedOption<'_>], ctx: &Context, cmd: &CommandInteraction, sender: &User, guild: &GuildId,
+                 game_data: &GameData) -> Result<(), AnglerError> {
+    let claim = options.iter().any(|option| option.name == "claim" && matches!(option.value, ResolvedValue::Boolean(true)));
+
+    let board = ContractBoard::current(guild.get(), game_data)?;
+
+    let (claimed, progress) = modify_user(guild.get(), &sender.id, |user| {
+        user.contracts.sync(&board);
+        let claimed = claim.then(|| claim_contracts(user, &board));
+        (claimed, user.contracts.clone())
+    });
+
+    let description = match claimed {
+        Some(Ok(Claim { contracts, reward })) => {
+            say!("{} claimed {} contracts for ${}", sender.name, contracts.len(), reward);
+            format!("You claimed {} contracts for **${}**!", contracts.len(), reward)
+        }
+        Some(Err(e)) => e.to_string(),
+        None => "New contracts arrive with every shop restock.".to_string(),
+    };
+
+    let fields = board.contracts.iter().enumerate().map(|(i, contract)| {
+        let status = if progress.claimed[i] {
+            "**Claimed**".to_string()
+        } else if progress.progress[i] >= contract.goal() {
+            "**Complete!** Claim it with `/contracts claim:True`".to_string()
+        } else {
+            format!("Progress: {}/{}", progress.progress[i], contract.goal())
+        };
+        (contract.to_string(), format!("Reward: ${}\n{}", contract.reward, status), false)
+    }).collect::<Vec<(String, String, bool)>>();
+
+    let shop = Shop::load(&game_data.rods, &game_data.bait)?;
+
+    // create the embedded message
+    let embed = CreateEmbed::new()
+        .title("Daily Contracts")
+        .thumbnail("attachment://rod_with_fish.png")
+        .description(description)
+        .fields(fields)
+        .footer(CreateEmbedFooter::new(format!("New contracts in {}", shop.get_time_until_restock())))
+        .color(Colour::DARK_GOLD);
+
+    // create the message builder
+    let builder = CreateInteractionResponse::Message(CreateInteractionResponseMessage::new()
+        .embed(embed)
+        .add_files(asset("rod_with_fish.png").await));
+
+    // send the message
+    cmd.create_response(&ctx.http, builder).await?;
+    Ok(())
+}
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("contracts")
+        .description("View today's contracts and claim the rewards for finished ones")
+        .dm_permission(false)
+        .add_option(CreateCommandOption::new(CommandOptionType::Boolean, "claim", "Claim the rewards for every finished contract"))
+}
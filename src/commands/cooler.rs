@@ -0,0 +1,57 @@
+use chrono::Local;
+use serenity::all::{Colour, CommandInteraction, Context, CreateCommand, CreateEmbed, CreateEmbedFooter,
+                    CreateInteractionResponse, CreateInteractionResponseMessage, GuildId, User};
+use crate::commands::asset;
+use crate::data::registry::GameData;
+use crate::data::userfile::store;
+use crate::error::AnglerError;
+
+/// Discord only shows 25 fields in an embed
+const MAX_FIELDS: usize = 25;
+
+pub async fn run(ctx: &Context, cmd: &CommandInteraction, sender: &User, guild: &GuildId,
+                 game_data: &GameData) -> Result<(), AnglerError> {
+    let user_data = store().read(&sender.id, guild.get());
+    let now = Local::now().timestamp();
+
+    let capacity = game_data.config.cooler.capacity(&user_data.get_rod(&game_data.rods)?);
+
+    let mut total = 0;
+    let fish = user_data.cooler.iter().map(|fish| {
+        let value = fish.value(game_data, now).unwrap_or(0);
+        total += value;
+        (format!("{} {}", fish.rarity, fish.species),
+         format!("{}lbs, caught {} hours ago\nWorth ${}", fish.weight, fish.hours_old(now) as u32, value),
+         true)
+    }).collect::<Vec<(String, String, bool)>>();
+
+    let description = if fish.is_empty() {
+        "Your cooler is empty! Go catch some fish with `/fish`.".to_string()
+    } else {
+        format!("Fish lose value the longer they sit, run `/sell` to cash in!\n**Total value: ${}**", total)
+    };
+
+    // create the embedded message
+    let embed = CreateEmbed::new()
+        .title(format!("{}'s Cooler", sender.global_name.clone().unwrap_or(sender.name.clone())))
+        .thumbnail("attachment://rod_with_fish.png")
+        .description(description)
+        .fields(fish.into_iter().take(MAX_FIELDS))
+        .footer(CreateEmbedFooter::new(format!("{}/{} fish", user_data.cooler.len(), capacity)))
+        .color(Colour::BLUE);
+
+    // create the message builder
+    let builder = CreateInteractionResponse::Message(CreateInteractionResponseMessage::new()
+        .embed(embed)
+        .add_files(asset("rod_with_fish.png").await));
+
+    // send the message
+    cmd.create_response(&ctx.http, builder).await?;
+    Ok(())
+}
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("cooler")
+        .description("View the fish in your cooler and what they're worth")
+        .dm_permission(false)
+}
@@ -0,0 +1,30 @@
+use serenity::all::{CommandOptionType, CreateCommand, CreateCommandOption, GuildId, ResolvedOption, ResolvedValue, User};
+use crate::data::registry::GameData;
+use crate::data::userfile::modify_user;
+use crate::engine::inventory::equip;
+use crate::say;
+
+pub fn run(options: &[ResolvedOption], sender: &User, guild: &GuildId, game_data: &GameData) -> String {
+    let Some(ResolvedOption { value: ResolvedValue::Integer(slot), .. }) = options.first() else {
+        return "Invalid rod!".to_string();
+    };
+    let slot = *slot as usize - 1;
+
+    match modify_user(guild.get(), &sender.id, |user| equip(user, slot, game_data)) {
+        Ok(rod) => {
+            say!("{} equipped their {}", sender.name, rod);
+            format!("You are now using your {}!", rod)
+        }
+        Err(e) => e.to_string(),
+    }
+}
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("equip")
+        .description("Switch to another rod from your inventory")
+        .dm_permission(false)
+        .add_option(CreateCommandOption::new(CommandOptionType::Integer, "rod",
+                                             "The inventory number of the rod to use")
+            .min_int_value(1)
+            .required(true))
+}
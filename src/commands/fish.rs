@@ -1,117 +1,165 @@
 use std::sync::Arc;
-use std::time::Duration;
-use rand::{Rng, thread_rng};
-use serenity::all::{ChannelId, Colour, Context, CreateAttachment, CreateCommand, CreateEmbed,
-                    CreateMessage, GuildId, Http, Mentionable, Timestamp, User, UserId};
-use crate::{nay, say, wow};
-use crate::data::fish::{Fish, FishData};
-use crate::data::multipliers::MultiplierData;
-use crate::data::userfile::{read_userfile, update_userfile, UserValues};
-
-const WEIGHT_ADD_TIME: f32 = 0.05;
-
-pub struct FishCatch {
-    pub(crate) fish: Fish,
-    pub(crate) user_file: UserValues,
-    pub(crate) will_catch: bool,
-    pub(crate) override_special: bool,
-}
+use std::time::{Duration, Instant};
+use chrono::Local;
+use serenity::all::{ButtonStyle, ChannelId, Colour, CommandOptionType, Context, CreateButton, CreateCommand,
+                    CreateCommandOption, CreateEmbed, CreateInteractionResponse, CreateInteractionResponseMessage,
+                    CreateMessage, EditMessage, GuildId, Http, Mentionable, ResolvedOption, ResolvedValue, Timestamp,
+                    User, UserId};
+use crate::{nay, say, wow, yay};
+use crate::commands::achievements::announcement;
+use crate::commands::asset;
+use crate::commands::contracts::finished_note;
+use crate::data::bait::BaitData;
+use crate::data::casts::{add_pending_cast, pending_casts, take_pending_cast, PendingCast};
+use crate::data::contracts::ContractBoard;
+use crate::data::events::Event;
+use crate::data::fish::Fish;
+use crate::data::guildfile::modify_guild;
+use crate::data::records::record_catch;
+use crate::data::registry::GameData;
+use crate::data::rng::new_seed;
+use crate::data::userfile::{modify_user, store, UserValues};
+use crate::engine::achievements::check_achievements;
+use crate::engine::contracts::{track_contracts, ContractEvent};
+use crate::engine::reel::{apply_reel, reel_window, Reel};
+use crate::engine::cast::{cast, Cast, CastOutcome};
+use crate::engine::catch::{resolve_catch, CatchOutcome};
+use crate::error::AnglerError;
 
-// todo: clown fish event
+const REEL_ID: &str = "fish:reel";
 
-async fn turtle(http: Arc<Http>, channel: ChannelId, sender: UserId, guild: &GuildId, mut catch: FishCatch) {
-    // create the embedded message
-    let embed = CreateEmbed::new()
-        .title("TURTLE EVENT")
-        .thumbnail("attachment://turtle.png")
-        .description(format!("A turtle stole your **{}**!\n:turtle::turtle::turtle:", catch.fish))
-        .color(Colour::DARK_GREEN)
-        .timestamp(Timestamp::now());
+/// Shown on catches that resolved while the bot was offline
+const AWAY_NOTE: &str = "*While the bot was away...*\n";
 
-    // create the message builder
-    let builder = CreateMessage::new()
-        .content(format!("{}", sender.mention()))
-        .embed(embed)
-        .add_file(CreateAttachment::path("./assets/turtle.png").await.unwrap());
+/// The embed announcing a random event that happened to `fish`
+fn event_embed(event: &Event, fish: &Fish) -> CreateEmbed {
+    let mut embed = CreateEmbed::new()
+        .title(event.embed.title.as_str())
+        .description(event.embed.describe(fish))
+        .color(event.embed.colour().map(Colour::new).unwrap_or(Colour::DARK_GREEN))
+        .timestamp(Timestamp::now());
 
-    // send the message
-    let msg = channel.send_message(&http, builder).await;
-    if let Err(e) = msg {
-        nay!("Failed to send message: {}", e);
+    if let Some(image) = &event.embed.image {
+        embed = embed.thumbnail(format!("attachment://{}", image));
     }
-
-    catch.user_file.cast = false;
-    update_userfile(&sender, catch.user_file, guild.get());
+    embed
 }
 
-pub async fn catch(http: Arc<Http>, channel: ChannelId, sender: UserId, guild_id: GuildId, mut catch: FishCatch) {
-    let value = catch.fish.get_value().max(1);
-    let rod = catch.user_file.get_rod().clone();
-
-    // if the fish is too heavy for the rod, break the rod
-    let weight_limit = rod.get_weight_limit();
-    if catch.fish.weight > weight_limit as f32 && !catch.override_special {
-        let msg =
-            channel.send_message(&http,
-                                 CreateMessage::new().content(format!("{} Your line broke! The {}lb **{}** was too heavy!",
-                                                                      sender.mention(), catch.fish.weight, catch.fish))).await;
-        if let Err(e) = msg {
-            nay!("Failed to send message: {}", e);
-        }
-        catch.user_file.cast = false;
-        update_userfile(&sender, catch.user_file, guild_id.get());
-        return;
-    }
-
-    if !catch.will_catch && !catch.override_special {
-        let msg =
-            channel.send_message(&http,
-                                 CreateMessage::new().content(format!("{} A {}lb **{}** got away! Better luck next time!",
-                                                                      sender.mention(), catch.fish.weight, catch.fish))).await;
-        if let Err(e) = msg {
-            nay!("Failed to send message: {}", e);
-        }
-        catch.user_file.cast = false;
-        update_userfile(&sender, catch.user_file, guild_id.get());
-        return;
-    }
+/// Adds the event's embed to a catch message, along with its image if the message doesn't have it already
+async fn with_event(builder: CreateMessage, event: Option<&Event>, fish: &Fish, attached: Option<&str>) -> CreateMessage {
+    let Some(event) = event else {
+        return builder;
+    };
 
-    let turtle_chance = thread_rng().gen_range(0..100) >= 98;
-    if turtle_chance && !catch.override_special {
-        turtle(http, channel, sender, &guild_id, catch).await;
-        return;
+    let builder = builder.add_embed(event_embed(event, fish));
+    match event.embed.image.as_deref() {
+        Some(image) if Some(image) != attached => builder.add_files(asset(image).await),
+        _ => builder,
     }
+}
 
-    let fish_data = FishData::load();
-
-    if !catch.user_file.has_seen.contains(&catch.fish.fish_type.name) {
-        catch.user_file.has_seen.push(catch.fish.fish_type.name.clone());
-    }
-    catch.user_file.money += value;
-    catch.user_file.fish_caught += 1;
+#[allow(clippy::too_many_arguments)]
+async fn caught_message(sender: UserId, fish: &Fish, value: u32, xp: u32, level_up: Option<u32>, record: Option<&str>,
+                        cast: &Cast, user: &UserValues, game_data: &GameData, note: &str) -> CreateMessage {
+    let (level, into_level, level_xp) = game_data.config.leveling.progress(user.xp);
 
     // create the embedded message
-    let embed = CreateEmbed::new()
+    let mut embed = CreateEmbed::new()
         .title("You caught a fish!")
         .thumbnail("attachment://rod_with_fish.png")
-        .description(format!("You caught a **{}** at {}lbs!", catch.fish, catch.fish.weight))
+        .description(format!("You caught a **{}** at {}lbs!", fish, fish.weight))
         .fields(vec![
             ("Value:", format!("${}", value), true),
-            ("New Balance:", format!("${}", catch.user_file.money), true),
+            ("Cooler:", format!("{}/{}", user.cooler.len(), game_data.config.cooler.capacity(&cast.rod)), true),
         ])
-        .field("Your Rod:", format!("{}", rod), false)
+        .field("Your Rod:", match &cast.bait {
+            Some(bait) => format!("{}\nBaited with {}", cast.rod, bait),
+            None => format!("{}", cast.rod),
+        }, false)
         .fields(vec! [
-        ("Fish caught:", format!("{}", catch.user_file.fish_caught), true),
-        ("Unique catches:", format!("{}/{}", catch.user_file.has_seen.len(), fish_data.fish.len()), true)
+        ("Fish caught:", format!("{}", user.fish_caught), true),
+        ("Unique catches:", format!("{}/{}", user.has_seen.len(), game_data.fish.fish.len()), true)
     ])
+        .field("XP:", format!("+{} (level {}: {}/{})", xp, level, into_level, level_xp), false)
         .color(Colour::DARK_TEAL)
         .timestamp(Timestamp::now());
 
+    if let Some(level) = level_up {
+        embed = embed.field("Level up!", format!("You are now level {}!", level), false);
+    }
+
+    if let Some(record) = record {
+        embed = embed.field(record, format!("Check `/records {}` to see where it stands.", fish.fish_type.name), false);
+    }
+
     // create the message builder
-    let builder = CreateMessage::new()
-        .content(format!("{} has caught a fish!", sender.mention()))
+    CreateMessage::new()
+        .content(format!("{}{} has caught a fish! Run `/sell` when you're ready to cash in.", note, sender.mention()))
         .embed(embed)
-        .add_file(CreateAttachment::path("./assets/rod_with_fish.png").await.unwrap());
+        .add_files(asset("rod_with_fish.png").await)
+}
+
+pub async fn catch(http: Arc<Http>, channel: ChannelId, sender: UserId, guild_id: GuildId, cast: Cast,
+                   game_data: Arc<GameData>, away: bool) {
+    let note = if away { AWAY_NOTE } else { "" };
+
+    // apply the catch to the user's current values rather than a copy taken when they cast
+    let caught_at = Local::now().timestamp();
+    let board = ContractBoard::current(guild_id.get(), &game_data)
+        .map_err(|e| nay!("Failed to load contracts for guild {}: {}", guild_id, e))
+        .ok();
+    let events = game_data.events.enabled(&store().read_guild(guild_id.get()).events);
+    let (outcome, finished, unlocked, user) = modify_user(guild_id.get(), &sender, |user| {
+        let outcome = resolve_catch(user, &cast, &game_data, &events, caught_at);
+        let finished = match (&outcome, &board) {
+            (CatchOutcome::Caught { fish, .. } | CatchOutcome::CoolerFull { fish, .. }, Some(board))
+                if !cast.override_special => track_contracts(user, board, ContractEvent::Catch(fish)),
+            _ => Vec::new(),
+        };
+        let unlocked = check_achievements(user, &game_data);
+        (outcome, finished, unlocked, user.clone())
+    });
+
+    // a server record outranks a personal best, so only the bigger news is announced.
+    // Fish let go from a full cooler don't count for records or the tournament.
+    let record = match (&outcome, outcome.kept()) {
+        (CatchOutcome::Caught { personal_best, .. }, Some(fish)) if !cast.override_special => {
+            let value = fish.get_value(&game_data).max(1);
+            let server_record = modify_guild(guild_id.get(), |guild| {
+                if let Some(tournament) = &mut guild.tournament {
+                    tournament.record_catch(sender.get(), fish, value, caught_at);
+                }
+                record_catch(&mut guild.records, sender.get(), fish, value, caught_at)
+            });
+            if server_record {
+                Some("New server record!")
+            } else {
+                personal_best.then_some("New personal best!")
+            }
+        }
+        _ => None,
+    };
+
+    let builder = match &outcome {
+        CatchOutcome::LineBroke { fish } =>
+            CreateMessage::new().content(format!("{}{} Your line broke! The {}lb **{}** was too heavy!",
+                                                 note, sender.mention(), fish.weight, fish)),
+        CatchOutcome::Escaped { fish } =>
+            CreateMessage::new().content(format!("{}{} A {}lb **{}** got away! Better luck next time!",
+                                                 note, sender.mention(), fish.weight, fish)),
+        CatchOutcome::Stolen { fish, event } =>
+            with_event(CreateMessage::new().content(format!("{}{}", note, sender.mention())), Some(event), fish, None).await,
+        CatchOutcome::CoolerFull { fish, xp, level_up, event, .. } => {
+            let level_up = level_up.map(|level| format!(" **Level up!** You are now level {}!", level)).unwrap_or_default();
+            let builder = CreateMessage::new().content(format!("{}{} You reeled in a {}lb **{}**, but your cooler is full so you let it go! Run `/sell` to make room. (+{} XP){}",
+                                                               note, sender.mention(), fish.weight, fish, xp, level_up));
+            with_event(builder, event.as_ref(), fish, None).await
+        }
+        CatchOutcome::Caught { fish, value, xp, level_up, event, .. } => {
+            let builder = caught_message(sender, fish, *value, *xp, *level_up, record, &cast, &user, &game_data, note).await;
+            with_event(builder, event.as_ref(), fish, Some("rod_with_fish.png")).await
+        }
+    };
 
     // send the message
     let msg = channel.send_message(&http, builder).await;
@@ -119,61 +167,171 @@ pub async fn catch(http: Arc<Http>, channel: ChannelId, sender: UserId, guild_id
         nay!("Failed to send message: {}", e);
     }
 
-    // update the user file
-    catch.user_file.cast = false;
-    update_userfile(&sender, catch.user_file, guild_id.get());
+    if !finished.is_empty() || !unlocked.is_empty() {
+        let builder = CreateMessage::new().content(format!("{}{}{}", sender.mention(), finished_note(&finished),
+                                                           announcement(&unlocked)));
+        if let Err(e) = channel.send_message(&http, builder).await {
+            nay!("Failed to send message: {}", e);
+        }
+    }
 }
 
-pub fn run(ctx: &Context, channel: &ChannelId, sender: &User, guild: &GuildId) -> String {
-    let mut user_file = read_userfile(&sender.id, guild.get());
-    if user_file.cast {
-        return "You have already cast your line!".to_string();
+/// Resolves a persisted cast, unless it has already been resolved
+async fn resolve_pending(http: Arc<Http>, pending: PendingCast, game_data: Arc<GameData>, away: bool) {
+    if !take_pending_cast(pending.guild_id, pending.user_id, pending.cast.seed) {
+        return;
     }
-    user_file.cast = true;
-    update_userfile(&sender.id, user_file, guild.get());
-    user_file = read_userfile(&sender.id, guild.get());
 
-    let fish_data = FishData::load();
+    catch(http, ChannelId::new(pending.channel_id), UserId::new(pending.user_id), GuildId::new(pending.guild_id),
+          pending.cast, game_data, away).await;
+}
 
-    let rod = &user_file.get_rod();
+/// Posts the bite with a Reel button and waits for the caster to click it, then resolves the cast.
+/// If the bite can't be posted the cast resolves with the roll made when it was cast.
+async fn reel_in(ctx: Context, mut pending: PendingCast, game_data: Arc<GameData>) {
+    let channel = ChannelId::new(pending.channel_id);
+    let caster = UserId::new(pending.user_id);
 
-    let fish = Fish::random_fish(&fish_data, rod);
+    let bite = CreateMessage::new()
+        .content(format!("{} Something's biting! Reel it in!", caster.mention()))
+        .button(CreateButton::new(REEL_ID).label("Reel").style(ButtonStyle::Primary));
+    let mut message = match channel.send_message(&ctx.http, bite).await {
+        Ok(message) => message,
+        Err(e) => {
+            nay!("Failed to post bite for {}: {}", caster, e);
+            return resolve_pending(ctx.http.clone(), pending, game_data, false).await;
+        }
+    };
 
-    let multipliers = MultiplierData::load();
+    let window = reel_window(&pending.cast.fish);
+    let bit_at = Instant::now();
+    let reel = loop {
+        let remaining = window.saturating_sub(bit_at.elapsed());
+        let Some(press) = message.await_component_interaction(&ctx).timeout(remaining).await else {
+            break Reel::Missed;
+        };
 
-    let will_catch = thread_rng().gen_range(0..1000) <= (rod.get_catch_chance() + multipliers.catch_chance);
+        if press.user.id != caster {
+            let reply = CreateInteractionResponseMessage::new().content("That's not your line!").ephemeral(true);
+            if let Err(e) = press.create_response(&ctx.http, CreateInteractionResponse::Message(reply)).await {
+                nay!("Failed to respond to button: {}", e);
+            }
+            continue;
+        }
+
+        let reaction = bit_at.elapsed();
+        if let Err(e) = press.create_response(&ctx.http, CreateInteractionResponse::Acknowledge).await {
+            nay!("Failed to respond to button: {}", e);
+        }
+        break Reel::Reeled { reaction };
+    };
 
-    // get the time until catch and convert to miliseconds
-    let weight_catch_time_add = (fish.weight - fish.fish_type.avg_weight as f32) * WEIGHT_ADD_TIME;
-    let catch_time = ((rod.random_catch_time() + weight_catch_time_add) * 1000.0) as u64;
+    let content = match &reel {
+        Reel::Missed => format!("{} Too slow! The fish slipped off the hook.", caster.mention()),
+        Reel::Reeled { reaction } => format!("{} reeled in after {:.1} seconds!", caster.mention(), reaction.as_secs_f32()),
+    };
+    if let Err(e) = message.edit(&ctx.http, EditMessage::new().content(content).components(vec![])).await {
+        nay!("Failed to update bite message: {}", e);
+    }
 
-    let http = ctx.http.clone();
-    let channel_id = *channel;
-    let fish_clone= fish.clone();
-    let id = sender.id;
-    let guild_id = *guild;
-    let fish_weight = fish.weight;
-    let ufile = user_file.clone();
+    apply_reel(&mut pending.cast, &reel, &game_data.multipliers);
+    say!("{} {} their reel and {} land the fish (seed: {})", caster,
+        if let Reel::Reeled { reaction } = &reel { format!("took {}ms on", reaction.as_millis()) } else { "missed".to_string() },
+        if pending.cast.will_catch { "will" } else { "wont" }, pending.cast.seed);
+    resolve_pending(ctx.http.clone(), pending, game_data, false).await;
+}
 
-    // schedule the catch
+/// Waits for a persisted cast to come due, then lets the caster reel it in
+fn schedule_catch(ctx: Context, pending: PendingCast, game_data: Arc<GameData>) {
     tokio::spawn(async move {
-        tokio::time::sleep(Duration::from_millis(catch_time)).await;
-        catch(http, channel_id, id, guild_id, FishCatch {user_file, fish, will_catch, override_special: false}).await;
+        if let Some(remaining) = pending.remaining() {
+            tokio::time::sleep(Duration::from_millis(remaining)).await;
+        }
+        reel_in(ctx, pending, game_data).await;
     });
+}
 
-    if fish_clone.rarity.ident() > 3 {
-        wow!("{} {} catch a {} of {}lbs in {} seconds! Value: {}", sender.name,
-        if will_catch { "will" } else { "wont" }, fish_clone, fish_weight, catch_time / 1000u64, fish_clone.get_value());
+/// Picks up the casts that were still in the water when the bot last shut down.
+/// Overdue casts resolve right away with the roll made when they were cast, the rest are scheduled again.
+pub async fn restore_casts(ctx: &Context, game_data: Arc<GameData>) {
+    let pending = pending_casts();
+    if pending.is_empty() {
+        return;
+    }
+
+    yay!("Restoring {} casts from before the restart", pending.len());
+
+    for pending in pending {
+        if pending.remaining().is_some() {
+            schedule_catch(ctx.clone(), pending, game_data.clone());
+        } else {
+            resolve_pending(ctx.http.clone(), pending, game_data.clone(), true).await;
+        }
+    }
+}
+
+pub fn run(options: &[ResolvedOption], ctx: &Context, channel: &ChannelId, sender: &User, guild: &GuildId,
+           game_data: Arc<GameData>) -> Result<String, AnglerError> {
+    let bait = options.iter().find_map(|option| match option {
+        ResolvedOption { name: "bait", value: ResolvedValue::String(bait), .. } => Some(*bait),
+        _ => None,
+    });
+
+    let seed = new_seed();
+    let now = Local::now().timestamp();
+    let conditions = game_data.weather.current(guild.get());
+    let outcome = modify_user(guild.get(), &sender.id, |user| cast(user, &game_data, bait, &conditions, seed, now))?;
+
+    let cast = match outcome {
+        CastOutcome::Cast(cast) => cast,
+        CastOutcome::AlreadyCasting => return Ok("You have already cast your line!".to_string()),
+        CastOutcome::NoBait(bait) =>
+            return Ok(format!("You don't have any {}! Buy some from the `/shop`.", bait)),
+        CastOutcome::TooShallow(location) =>
+            return Ok(format!("Your rod can't reach the fish at the {}! You need one that fishes at least {}ft deep, or `/travel` somewhere shallower.",
+                              location, location.min_depth)),
+        CastOutcome::Tangled(until) =>
+            return Ok(format!("Your line is still tangled! You can cast again <t:{}:R>.", until)),
+    };
+
+    let fish = &cast.fish;
+    let fish_value = fish.get_value(&game_data);
+    let baited = cast.bait.as_ref().map(|bait| format!(" with {}", bait)).unwrap_or_default();
+    let at = cast.location.as_ref().map(|location| format!(" at the {}", location)).unwrap_or_default();
+    // everything after the seed is what `!replay` needs to roll the same cast again
+    let replay = format!("{} @ {}/{}", seed, cast.location.as_deref().unwrap_or_default(), conditions.log_key());
+    if fish.rarity.ident() > 3 {
+        wow!("{} {} catch a {} of {}lbs in {} seconds{}{} in {}! Value: {} (seed: {})", sender.name,
+        if cast.will_catch { "will" } else { "wont" }, fish, fish.weight, cast.catch_time / 1000u64, baited, at,
+            conditions.weather.name, fish_value, replay);
     } else {
-        say!("{} {} catch a {} of {}lbs in {} seconds! Value: {}", sender.name,
-        if will_catch { "will" } else { "wont" }, fish_clone, fish_weight, catch_time / 1000u64, fish_clone.get_value());
+        say!("{} {} catch a {} of {}lbs in {} seconds{}{} in {}! Value: {} (seed: {})", sender.name,
+        if cast.will_catch { "will" } else { "wont" }, fish, fish.weight, cast.catch_time / 1000u64, baited, at,
+            conditions.weather.name, fish_value, replay);
     }
 
-    format!("You have cast your {}.", ufile.get_rod())
+    let response = match &cast.bait {
+        Some(bait) => format!("You have cast your {}{} baited with {}.", cast.rod, at, bait),
+        None => format!("You have cast your {}{}.", cast.rod, at),
+    };
+    let response = format!("{} ({})", response, conditions);
+
+    // persist the cast before scheduling it so a restart can't lose the fish
+    let pending = PendingCast::new(guild.get(), sender.id.get(), channel.get(), *cast);
+    add_pending_cast(pending.clone());
+    schedule_catch(ctx.clone(), pending, game_data);
+
+    Ok(response)
 }
 
-pub fn register(motd: String) -> CreateCommand {
+pub fn register(motd: String, bait_data: &BaitData) -> CreateCommand {
+    let mut bait = CreateCommandOption::new(CommandOptionType::String, "bait", "Bait to put on the hook");
+    for name in bait_data.bait.iter().map(|bait| bait.name.as_str()) {
+        bait = bait.add_string_choice(name, name);
+    }
+
     CreateCommand::new("fish")
         .description(motd)
         .dm_permission(false)
-}
\ No newline at end of file
+        .add_option(bait)
+}
@@ -0,0 +1,52 @@
+use chrono::Local;
+use serenity::all::{Colour, CommandInteraction, Context, CreateCommand, CreateEmbed, CreateEmbedFooter,
+                    CreateInteractionResponse, CreateInteractionResponseMessage, GuildId};
+use crate::data::registry::GameData;
+use crate::data::userfile::store;
+use crate::data::weather::local_time;
+use crate::error::AnglerError;
+
+/// How many upcoming weather changes the forecast shows
+const FORECAST_LENGTH: usize = 4;
+
+pub async fn run(ctx: &Context, cmd: &CommandInteraction, guild: &GuildId, game_data: &GameData) -> Result<(), AnglerError> {
+    let weather_data = &game_data.weather;
+    let utc_offset = store().read_guild(guild.get()).utc_offset;
+    let now = Local::now().timestamp();
+    let conditions = weather_data.conditions(guild.get(), utc_offset, now);
+
+    let local_time = local_time(utc_offset, now).map(|time| time.format("%H:%M").to_string()).unwrap_or_default();
+
+    let upcoming = weather_data.forecast(guild.get(), now, FORECAST_LENGTH).into_iter()
+        .map(|(start, weather)| format!("<t:{}:t> {}", start, weather))
+        .collect::<Vec<String>>();
+
+    // species that only bite in some conditions or seasons and are biting now
+    let special = game_data.fish.fish.iter()
+        .filter(|fish| !fish.weather.is_empty() || !fish.times.is_empty() || fish.is_seasonal())
+        .filter(|fish| conditions.allows(fish))
+        .map(|fish| fish.name.clone())
+        .collect::<Vec<String>>();
+
+    let embed = CreateEmbed::new()
+        .title("Forecast")
+        .description(format!("**{}**: {}\n**{}**: {}", conditions.weather, conditions.weather.description,
+                             conditions.time, conditions.time.description))
+        .fields(vec![
+            ("Local time:", local_time, true),
+            ("Coming up:", upcoming.join("\n"), true),
+            ("Only biting now:", if special.is_empty() { "Nothing special".to_string() } else { special.join(", ") }, false),
+        ])
+        .footer(CreateEmbedFooter::new("Admins can set the server's timezone with /admin timezone"))
+        .color(Colour::BLUE);
+
+    let builder = CreateInteractionResponse::Message(CreateInteractionResponseMessage::new().embed(embed));
+    cmd.create_response(&ctx.http, builder).await?;
+    Ok(())
+}
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("forecast")
+        .description("Check the weather and time of day, and what's coming up")
+        .dm_permission(false)
+}
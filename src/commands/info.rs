@@ -1,22 +1,31 @@
-use serenity::all::{Colour, CommandInteraction, Context, CreateAttachment, CreateCommand, CreateEmbed,
+use serenity::all::{Colour, CommandInteraction, Context, CreateCommand, CreateEmbed,
                     CreateInteractionResponse, CreateInteractionResponseMessage, GuildId, Timestamp, User};
-use crate::data::fish::FishData;
-use crate::data::userfile::read_userfile;
-use crate::nay;
+use crate::data::registry::GameData;
+use crate::commands::asset;
+use crate::data::userfile::store;
+use crate::error::AnglerError;
 
-pub async fn run(ctx: &Context, cmd: &CommandInteraction, sender: &User, guild: &GuildId) {
-    let user_data = read_userfile(&sender.id, guild.get());
+pub async fn run(ctx: &Context, cmd: &CommandInteraction, sender: &User, guild: &GuildId,
+                 game_data: &GameData) -> Result<(), AnglerError> {
+    let user_data = store().read(&sender.id, guild.get());
 
-    let fish_data = FishData::load();
+    let fish_data = &game_data.fish;
 
-    let rod = user_data.get_rod();
+    let rod = user_data.get_rod(&game_data.rods)?;
+
+    let (level, into_level, level_xp) = game_data.config.leveling.progress(user_data.xp);
 
     // create the embedded message
     let embed = CreateEmbed::new()
         .title(format!("{}'s Info", sender.global_name.clone().unwrap_or(sender.name.clone())))
         .thumbnail("attachment://fishingrod_smaller.png")
-        .description("Your information")
+        .description(match &user_data.title {
+            Some(title) => format!("*{}*", title),
+            None => "Your information".to_string(),
+        })
         .field("Balance:", format!("${}", user_data.money), false)
+        .field("Location:", game_data.locations.current(user_data.location.as_deref()).to_string(), false)
+        .field(format!("Level {}:", level), format!("{}/{} XP to level {}", into_level, level_xp, level + 1), false)
         .fields(vec! [
             ("Fish caught:", format!("{}", user_data.fish_caught), true),
             ("Unique catches:", format!("{}/{}", user_data.has_seen.len(), fish_data.fish.len()), true)
@@ -33,12 +42,11 @@ pub async fn run(ctx: &Context, cmd: &CommandInteraction, sender: &User, guild:
     // create the message builder
     let builder = CreateInteractionResponse::Message(CreateInteractionResponseMessage::new()
         .embed(embed)
-        .add_file(CreateAttachment::path("./assets/fishingrod_smaller.png").await.unwrap()));
+        .add_files(asset("fishingrod_smaller.png").await));
 
     // send the message
-    if let Err(err) = cmd.create_response(&ctx.http, builder).await {
-        nay!("Failed to respond to command: {}", err)
-    }
+    cmd.create_response(&ctx.http, builder).await?;
+    Ok(())
 }
 
 pub fn register() -> CreateCommand {
@@ -0,0 +1,70 @@
+use serenity::all::{Colour, CommandInteraction, Context, CreateCommand, CreateEmbed, CreateEmbedFooter,
+                    CreateInteractionResponse, CreateInteractionResponseMessage, GuildId, User};
+use crate::commands::asset;
+use crate::data::registry::GameData;
+use crate::data::userfile::store;
+use crate::error::AnglerError;
+use crate::hey;
+
+/// Discord only shows 25 fields in an embed, and the bait takes one of them
+const MAX_ROD_FIELDS: usize = 24;
+
+pub async fn run(ctx: &Context, cmd: &CommandInteraction, sender: &User, guild: &GuildId,
+                 game_data: &GameData) -> Result<(), AnglerError> {
+    let user_data = store().read(&sender.id, guild.get());
+
+    let multipliers = &game_data.multipliers;
+
+    let rods = user_data.rods.iter().enumerate().map(|(slot, owned)| {
+        let equipped = if slot == user_data.equipped { " (equipped)" } else { "" };
+
+        // a rod removed from rods.json is still listed so the slot numbers line up
+        match owned.to_rod(&game_data.rods) {
+            Ok(rod) => (format!("{}: {}{}", slot + 1, rod, equipped),
+                        format!("Rarity: {}\nSells for: ${}", rod.base.rarity, rod.base.get_resale_value(multipliers)),
+                        false),
+            Err(e) => {
+                hey!("{} owns a rod that can't be loaded: {}", sender.name, e);
+                (format!("{}: {}{}", slot + 1, owned.name, equipped), "This rod has washed away".to_string(), false)
+            }
+        }
+    }).collect::<Vec<(String, String, bool)>>();
+
+    let bait = if user_data.bait.is_empty() {
+        "None, buy some from the `/shop`!".to_string()
+    } else {
+        user_data.bait.iter().map(|stack| format!("{} x{}", stack.name, stack.count)).collect::<Vec<String>>().join("\n")
+    };
+
+    let hidden = rods.len().saturating_sub(MAX_ROD_FIELDS);
+    let footer = if hidden > 0 {
+        format!("{} rods, +{} more not shown", user_data.rods.len(), hidden)
+    } else {
+        format!("{} rods", user_data.rods.len())
+    };
+
+    // create the embedded message
+    let embed = CreateEmbed::new()
+        .title(format!("{}'s Rods", sender.global_name.clone().unwrap_or(sender.name.clone())))
+        .thumbnail("attachment://fishingrod_smaller.png")
+        .description("Run `/equip <#>` to switch rods\nRun `/sellrod <#>` to sell a rod")
+        .fields(rods.into_iter().take(MAX_ROD_FIELDS))
+        .field("Bait:", bait, false)
+        .footer(CreateEmbedFooter::new(footer))
+        .color(Colour::TEAL);
+
+    // create the message builder
+    let builder = CreateInteractionResponse::Message(CreateInteractionResponseMessage::new()
+        .embed(embed)
+        .add_files(asset("fishingrod_smaller.png").await));
+
+    // send the message
+    cmd.create_response(&ctx.http, builder).await?;
+    Ok(())
+}
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("inventory")
+        .description("View the rods and bait you own")
+        .dm_permission(false)
+}
@@ -0,0 +1,107 @@
+use serenity::all::{ButtonStyle, Colour, CommandInteraction, CommandOptionType, ComponentInteraction, Context,
+                    CreateActionRow, CreateButton, CreateCommand, CreateCommandOption, CreateEmbed,
+                    CreateEmbedFooter, CreateInteractionResponse, CreateInteractionResponseMessage, GuildId,
+                    ResolvedOption, ResolvedValue, UserId};
+use crate::data::leaderboard::Category;
+use crate::data::userfile::store;
+use crate::error::AnglerError;
+
+const PAGE_SIZE: usize = 10;
+
+/// Button ids look like `leaderboard:<category>:<page>`
+const BUTTON_PREFIX: &str = "leaderboard:";
+
+fn button_id(category: Category, page: usize) -> String {
+    format!("{}{}:{}", BUTTON_PREFIX, category.id(), page)
+}
+
+/// One page of a guild's leaderboard, with the viewer's own rank in the footer
+fn leaderboard_page(guild: GuildId, category: Category, page: usize, viewer: UserId) -> CreateInteractionResponseMessage {
+    let ranking = store().leaderboard(guild.get(), category);
+
+    let pages = ranking.len().div_ceil(PAGE_SIZE).max(1);
+    let page = page.min(pages - 1);
+
+    let lines = ranking.iter().enumerate()
+        .skip(page * PAGE_SIZE)
+        .take(PAGE_SIZE)
+        .map(|(rank, (id, score))| format!("**#{}** <@{}> - {}", rank + 1, id, category.format(*score)))
+        .collect::<Vec<String>>();
+
+    let description = if lines.is_empty() {
+        "Nobody is on this leaderboard yet, go fishing!".to_string()
+    } else {
+        lines.join("\n")
+    };
+
+    let own_rank = match ranking.iter().position(|(id, _)| *id == viewer) {
+        Some(rank) => format!("Your rank: #{} of {}", rank + 1, ranking.len()),
+        None => "You're not ranked yet".to_string(),
+    };
+
+    let embed = CreateEmbed::new()
+        .title(format!("{} Leaderboard", category.title()))
+        .description(description)
+        .footer(CreateEmbedFooter::new(format!("Page {}/{} • {}", page + 1, pages, own_rank)))
+        .color(Colour::GOLD);
+
+    let buttons = CreateActionRow::Buttons(vec![
+        CreateButton::new(button_id(category, page.saturating_sub(1)))
+            .label("Previous")
+            .style(ButtonStyle::Secondary)
+            .disabled(page == 0),
+        CreateButton::new(button_id(category, page + 1))
+            .label("Next")
+            .style(ButtonStyle::Secondary)
+            .disabled(page + 1 >= pages),
+    ]);
+
+    CreateInteractionResponseMessage::new()
+        .embed(embed)
+        .components(vec![buttons])
+}
+
+pub async fn run(options: &[ResolvedOption<'_>], ctx: &Context, cmd: &CommandInteraction,
+                 guild: &GuildId) -> Result<(), AnglerError> {
+    let category = options.iter().find_map(|option| match option {
+        ResolvedOption { name: "category", value: ResolvedValue::String(id), .. } => Category::from_id(id),
+        _ => None,
+    }).unwrap_or(Category::Balance);
+
+    let page = leaderboard_page(*guild, category, 0, cmd.user.id);
+    cmd.create_response(&ctx.http, CreateInteractionResponse::Message(page)).await?;
+    Ok(())
+}
+
+/// Whether a button belongs to a leaderboard
+pub fn is_leaderboard_button(component: &ComponentInteraction) -> bool {
+    component.data.custom_id.starts_with(BUTTON_PREFIX)
+}
+
+/// Turns the page when a leaderboard button is pressed
+pub async fn turn_page(ctx: &Context, component: &ComponentInteraction) -> Result<(), AnglerError> {
+    let invalid = || AnglerError::InvalidOption("That leaderboard has expired, run `/leaderboard` again!".to_string());
+
+    let (category, page) = component.data.custom_id.strip_prefix(BUTTON_PREFIX)
+        .and_then(|id| id.split_once(':'))
+        .ok_or_else(invalid)?;
+    let category = Category::from_id(category).ok_or_else(invalid)?;
+    let page = page.parse::<usize>().map_err(|_| invalid())?;
+    let guild = component.guild_id.ok_or_else(invalid)?;
+
+    let page = leaderboard_page(guild, category, page, component.user.id);
+    component.create_response(&ctx.http, CreateInteractionResponse::UpdateMessage(page)).await?;
+    Ok(())
+}
+
+pub fn register() -> CreateCommand {
+    let mut category = CreateCommandOption::new(CommandOptionType::String, "category", "What to rank players by");
+    for option in Category::ALL {
+        category = category.add_string_choice(option.title(), option.id());
+    }
+
+    CreateCommand::new("leaderboard")
+        .description("See the best anglers in this server")
+        .dm_permission(false)
+        .add_option(category)
+}
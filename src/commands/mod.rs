@@ -1,7 +1,36 @@
+use serenity::all::CreateAttachment;
+use crate::hey;
+
 pub mod fish;
 pub mod shop;
 pub(crate) mod buy;
 pub(crate) mod balance;
 pub(crate) mod info;
 pub(crate) mod rod_info;
-pub(crate) mod bestiary;
\ No newline at end of file
+pub(crate) mod bestiary;
+pub(crate) mod admin;
+pub(crate) mod inventory;
+pub(crate) mod equip;
+pub(crate) mod sellrod;
+pub(crate) mod sell;
+pub(crate) mod cooler;
+pub(crate) mod leaderboard;
+pub(crate) mod records;
+pub(crate) mod achievements;
+pub(crate) mod contracts;
+pub(crate) mod tournament;
+pub(crate) mod pay;
+pub(crate) mod trade;
+pub(crate) mod travel;
+pub(crate) mod forecast;
+
+/// Loads an image from ./assets. A missing image is logged and the message goes out without it.
+pub async fn asset(name: &str) -> Option<CreateAttachment> {
+    match CreateAttachment::path(format!("./assets/{}", name)).await {
+        Ok(attachment) => Some(attachment),
+        Err(e) => {
+            hey!("Failed to load asset {}: {}", name, e);
+            None
+        }
+    }
+}
@@ -0,0 +1,53 @@
+use serenity::all::{CommandOptionType, CreateCommand, CreateCommandOption, GuildId, Mentionable, ResolvedOption,
+                    ResolvedValue, User};
+use crate::data::registry::GameData;
+use crate::data::userfile::modify_users;
+use crate::engine::trade::{pay, Payment};
+use crate::say;
+
+pub fn run(options: &[ResolvedOption], sender: &User, guild: &GuildId, game_data: &GameData) -> String {
+    let mut receiver = None;
+    let mut amount = 0;
+    for option in options {
+        match (option.name, &option.value) {
+            ("user", ResolvedValue::User(user, _)) => receiver = Some(*user),
+            // registered with a u32 maximum, anything bigger is more than anyone has
+            ("amount", ResolvedValue::Integer(value)) => amount = u32::try_from(*value).unwrap_or(u32::MAX),
+            _ => {}
+        }
+    }
+
+    let Some(receiver) = receiver else {
+        return "Who are you paying?".to_string();
+    };
+    if receiver.id == sender.id {
+        return "You can't pay yourself!".to_string();
+    }
+    if receiver.bot {
+        return "Bots don't need money!".to_string();
+    }
+
+    match modify_users(guild.get(), &sender.id, &receiver.id, |from, to| pay(from, to, amount, game_data)) {
+        Ok(Payment { received, tax }) => {
+            say!("{} paid {} ${} (${} tax)", sender.name, receiver.name, amount, tax);
+            if tax > 0 {
+                format!("You paid {} ${}! (${} went to the transfer tax)", receiver.mention(), received, tax)
+            } else {
+                format!("You paid {} ${}!", receiver.mention(), received)
+            }
+        }
+        Err(e) => e.to_string(),
+    }
+}
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("pay")
+        .description("Send money to another player")
+        .dm_permission(false)
+        .add_option(CreateCommandOption::new(CommandOptionType::User, "user", "Who to pay")
+            .required(true))
+        .add_option(CreateCommandOption::new(CommandOptionType::Integer, "amount", "How much to send")
+            .min_int_value(1)
+            .max_int_value(u32::MAX as u64)
+            .required(true))
+}
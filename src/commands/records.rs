@@ -0,0 +1,138 @@
+use serenity::all::{ButtonStyle, Colour, CommandInteraction, CommandOptionType, ComponentInteraction, Context,
+                    CreateActionRow, CreateButton, CreateCommand, CreateCommandOption, CreateEmbed, CreateEmbedFooter,
+                    CreateInteractionResponse, CreateInteractionResponseMessage, GuildId, ResolvedOption,
+                    ResolvedValue, User};
+use crate::commands::asset;
+use crate::data::records::{CatchRecord, SpeciesRecord};
+use crate::data::registry::{game_data, GameData};
+use crate::data::userfile::store;
+use crate::error::AnglerError;
+
+/// Species per page of server records, within Discord's 25 fields per embed
+const PAGE_SIZE: usize = 12;
+
+/// Button ids look like `records:<page>`
+const BUTTON_PREFIX: &str = "records:";
+
+fn server_record(record: &Option<CatchRecord>) -> String {
+    match record {
+        Some(record) => format!("{}lbs, ${} by <@{}>", record.weight, record.value, record.holder),
+        None => "None yet".to_string(),
+    }
+}
+
+fn personal_record(record: &Option<CatchRecord>) -> String {
+    match record {
+        Some(record) => format!("{}lbs, ${}", record.weight, record.value),
+        None => "None yet".to_string(),
+    }
+}
+
+/// One page of the guild's records, a species per field in alphabetical order
+fn records_page(guild: GuildId, page: usize, game_data: &GameData) -> CreateInteractionResponseMessage {
+    let mut records = store().read_guild(guild.get()).records.into_iter().collect::<Vec<(String, SpeciesRecord)>>();
+    records.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    let pages = records.len().div_ceil(PAGE_SIZE).max(1);
+    let page = page.min(pages - 1);
+
+    let description = if records.is_empty() {
+        "Nobody has set a record yet! Go catch some fish with `/fish`.".to_string()
+    } else {
+        "Run `/records <species>` to compare your own bests.".to_string()
+    };
+
+    let count = records.len();
+    let embed = CreateEmbed::new()
+        .title("Server Records")
+        .description(description)
+        .fields(records.into_iter().skip(page * PAGE_SIZE).take(PAGE_SIZE).map(|(species, record)| {
+            (species, format!("Heaviest: {}\nMost valuable: {}",
+                              server_record(&record.heaviest), server_record(&record.most_valuable)), true)
+        }))
+        .footer(CreateEmbedFooter::new(format!("Page {}/{} • {}/{} species", page + 1, pages, count,
+                                               game_data.fish.fish.len())))
+        .thumbnail("attachment://rod_with_fish.png")
+        .color(Colour::GOLD);
+
+    let buttons = CreateActionRow::Buttons(vec![
+        CreateButton::new(format!("{}{}", BUTTON_PREFIX, page.saturating_sub(1)))
+            .label("Previous")
+            .style(ButtonStyle::Secondary)
+            .disabled(page == 0),
+        CreateButton::new(format!("{}{}", BUTTON_PREFIX, page + 1))
+            .label("Next")
+            .style(ButtonStyle::Secondary)
+            .disabled(page + 1 >= pages),
+    ]);
+
+    CreateInteractionResponseMessage::new()
+        .embed(embed)
+        .components(vec![buttons])
+}
+
+pub async fn run(options: &[ResolvedOption<'_>], ctx: &Context, cmd: &CommandInteraction, sender: &User, guild: &GuildId,
+                 game_data: &GameData) -> Result<(), AnglerError> {
+    let species = options.iter().find_map(|option| match option.value {
+        ResolvedValue::String(species) if option.name == "species" => Some(species),
+        _ => None,
+    });
+
+    let message = match species {
+        Some(species) => {
+            let fish_type = game_data.fish.fish.iter().find(|fish| fish.name.eq_ignore_ascii_case(species))
+                .ok_or_else(|| AnglerError::UnknownFish(species.to_string()))?;
+            let server = store().read_guild(guild.get()).records.get(&fish_type.name).cloned().unwrap_or_default();
+            let personal = store().read(&sender.id, guild.get()).records
+                .get(&fish_type.name).cloned().unwrap_or_default();
+
+            let embed = CreateEmbed::new()
+                .title(format!("{} Records", fish_type.name))
+                .fields(vec![
+                    ("Server heaviest:", server_record(&server.heaviest), true),
+                    ("Server most valuable:", server_record(&server.most_valuable), true),
+                    ("\u{200b}", "\u{200b}".to_string(), false),
+                    ("Your heaviest:", personal_record(&personal.heaviest), true),
+                    ("Your most valuable:", personal_record(&personal.most_valuable), true),
+                ])
+                .thumbnail("attachment://rod_with_fish.png")
+                .color(Colour::GOLD);
+            CreateInteractionResponseMessage::new().embed(embed)
+        }
+        None => records_page(*guild, 0, game_data),
+    };
+
+    // create the message builder
+    let builder = CreateInteractionResponse::Message(message.add_files(asset("rod_with_fish.png").await));
+
+    // send the message
+    cmd.create_response(&ctx.http, builder).await?;
+    Ok(())
+}
+
+/// Whether a button belongs to the server records
+pub fn is_records_button(component: &ComponentInteraction) -> bool {
+    component.data.custom_id.starts_with(BUTTON_PREFIX)
+}
+
+/// Turns the page when a server records button is pressed
+pub async fn turn_page(ctx: &Context, component: &ComponentInteraction) -> Result<(), AnglerError> {
+    let invalid = || AnglerError::InvalidOption("Those records have expired, run `/records` again!".to_string());
+
+    let page = component.data.custom_id.strip_prefix(BUTTON_PREFIX)
+        .and_then(|page| page.parse::<usize>().ok())
+        .ok_or_else(invalid)?;
+    let guild = component.guild_id.ok_or_else(invalid)?;
+
+    let game_data = game_data(ctx).await;
+    let page = records_page(guild, page, &game_data);
+    component.create_response(&ctx.http, CreateInteractionResponse::UpdateMessage(page)).await?;
+    Ok(())
+}
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("records")
+        .description("View the server's heaviest and most valuable catches")
+        .dm_permission(false)
+        .add_option(CreateCommandOption::new(CommandOptionType::String, "species", "Show the records for one kind of fish"))
+}
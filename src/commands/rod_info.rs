@@ -1,15 +1,17 @@
 use serenity::all::{Colour, CommandInteraction, CommandOptionType,
-                    Context, CreateAttachment, CreateCommand,
+                    Context, CreateCommand,
                     CreateCommandOption, CreateEmbed, CreateInteractionResponse,
                     CreateInteractionResponseMessage, ResolvedOption,
                     ResolvedValue, Timestamp};
-use crate::data::rods::RodData;
-use crate::nay;
+use crate::commands::asset;
+use crate::data::registry::GameData;
+use crate::error::AnglerError;
 
-pub async fn run(options: &[ResolvedOption<'_>], ctx: &Context, cmd: &CommandInteraction) {
+pub async fn run(options: &[ResolvedOption<'_>], ctx: &Context, cmd: &CommandInteraction,
+                 game_data: &GameData) -> Result<(), AnglerError> {
     // get the current shop
-    let shop = crate::data::shop::Shop::load();
-    let rod_data = RodData::load();
+    let rod_data = &game_data.rods;
+    let shop = crate::data::shop::Shop::load(rod_data, &game_data.bait)?;
 
     // get the index of the item to buy
     if let Some(ResolvedOption {
@@ -17,8 +19,10 @@ pub async fn run(options: &[ResolvedOption<'_>], ctx: &Context, cmd: &CommandInt
                 }) = options.first() {
         let item_index = *item_index as usize - 1;
 
-        let rod_name = &shop.rods[item_index];
-        let rod = rod_data.get_base_by_name(rod_name).unwrap();
+        let rod_name = shop.rods.get(item_index)
+            .ok_or_else(|| AnglerError::InvalidOption("That isn't an item in today's shop!".to_string()))?;
+        let rod = rod_data.get_base_by_name(rod_name)
+            .ok_or_else(|| AnglerError::UnknownRod(rod_name.clone()))?;
 
         // create the embedded message
         let embed = CreateEmbed::new()
@@ -30,18 +34,20 @@ pub async fn run(options: &[ResolvedOption<'_>], ctx: &Context, cmd: &CommandInt
             .field("Avg Catch Rate:", format!("{}", rod.catch_rate), false)
             .field("Max Depth:", format!("{}", rod.depth), false)
             .field("Max Weight:", format!("{}", rod.weight_limit), false)
+            .field("Required Level:", format!("{}", rod.required_level.max(1)), false)
             .color(Colour::GOLD)
             .timestamp(Timestamp::now());
 
         // create the message builder
         let builder = CreateInteractionResponse::Message(CreateInteractionResponseMessage::new()
             .embed(embed)
-            .add_file(CreateAttachment::path("./assets/fishingrod_smaller.png").await.unwrap()));
+            .add_files(asset("fishingrod_smaller.png").await));
 
         // send the message
-        if let Err(err) = cmd.create_response(&ctx.http, builder).await {
-            nay!("Failed to respond to command: {}", err)
-        }
+        cmd.create_response(&ctx.http, builder).await?;
+        Ok(())
+    } else {
+        Err(AnglerError::InvalidOption("Pick an item from the shop to view!".to_string()))
     }
 }
 
@@ -0,0 +1,63 @@
+use chrono::Local;
+use serenity::all::{CommandOptionType, CreateCommand, CreateCommandOption, GuildId, ResolvedOption, ResolvedValue, User};
+use crate::commands::contracts::finished_note;
+use crate::data::contracts::ContractBoard;
+use crate::data::fish::FishRarity;
+use crate::data::registry::GameData;
+use crate::data::userfile::modify_user;
+use crate::engine::contracts::{track_contracts, ContractEvent};
+use crate::engine::sell::{sell_fish, FishSale, SellFilter};
+use crate::error::AnglerError;
+use crate::{hey, say};
+
+pub fn run(options: &[ResolvedOption], sender: &User, guild: &GuildId, game_data: &GameData) -> Result<String, AnglerError> {
+    let mut filter = SellFilter::All;
+    for option in options {
+        let ResolvedValue::String(value) = option.value else {
+            continue;
+        };
+        if !matches!(filter, SellFilter::All) {
+            return Err(AnglerError::InvalidOption("Pick a species or a rarity to sell, not both!".to_string()));
+        }
+        filter = match option.name {
+            "species" => SellFilter::Species(value.to_string()),
+            "rarity" => SellFilter::Rarity(FishRarity::from_string(value)
+                .ok_or_else(|| AnglerError::InvalidOption(format!("{} isn't a rarity!", value)))?),
+            _ => continue,
+        };
+    }
+
+    let now = Local::now().timestamp();
+    let board = ContractBoard::current(guild.get(), game_data)?;
+    let result = modify_user(guild.get(), &sender.id, |user| {
+        sell_fish(user, &filter, game_data, now)
+            .map(|sale| {
+                let finished = track_contracts(user, &board, ContractEvent::Earn(sale.value));
+                (sale, finished)
+            })
+    });
+
+    Ok(match result {
+        Ok((FishSale { count, value, stale }, finished)) => {
+            if !stale.is_empty() {
+                hey!("{} sold fish that are no longer in fish.json: {}", sender.name, stale.join(", "));
+            }
+            say!("{} sold {} fish for ${}", sender.name, count, value);
+            format!("You sold {} fish for ${}!{}", count, value, finished_note(&finished))
+        }
+        Err(e) => e.to_string(),
+    })
+}
+
+pub fn register() -> CreateCommand {
+    let mut rarity = CreateCommandOption::new(CommandOptionType::String, "rarity", "Only sell fish of this rarity");
+    for name in ["Common", "Uncommon", "Rare", "Elusive", "Legendary", "Mythical"] {
+        rarity = rarity.add_string_choice(name, name);
+    }
+
+    CreateCommand::new("sell")
+        .description("Sell the fish in your cooler")
+        .dm_permission(false)
+        .add_option(CreateCommandOption::new(CommandOptionType::String, "species", "Only sell this kind of fish"))
+        .add_option(rarity)
+}
@@ -0,0 +1,44 @@
+use serenity::all::{CommandOptionType, CreateCommand, CreateCommandOption, GuildId, ResolvedOption, ResolvedValue, User};
+use crate::commands::achievements::announcement;
+use crate::commands::contracts::finished_note;
+use crate::data::contracts::ContractBoard;
+use crate::data::registry::GameData;
+use crate::data::userfile::modify_user;
+use crate::engine::achievements::check_achievements;
+use crate::engine::contracts::{track_contracts, ContractEvent};
+use crate::engine::inventory::{sell_rod, Sale};
+use crate::error::AnglerError;
+use crate::say;
+
+pub fn run(options: &[ResolvedOption], sender: &User, guild: &GuildId, game_data: &GameData) -> Result<String, AnglerError> {
+    let Some(ResolvedOption { value: ResolvedValue::Integer(slot), .. }) = options.first() else {
+        return Ok("Invalid rod!".to_string());
+    };
+    let slot = *slot as usize - 1;
+
+    let board = ContractBoard::current(guild.get(), game_data)?;
+    let result = modify_user(guild.get(), &sender.id, |user| {
+        sell_rod(user, slot, game_data).map(|sale| {
+            let finished = track_contracts(user, &board, ContractEvent::Earn(sale.value));
+            (sale, finished, check_achievements(user, game_data))
+        })
+    });
+
+    Ok(match result {
+        Ok((Sale { rod, value }, finished, unlocked)) => {
+            say!("{} sold their {} for ${}", sender.name, rod, value);
+            format!("You sold your {} for ${}!{}{}", rod, value, finished_note(&finished), announcement(&unlocked))
+        }
+        Err(e) => e.to_string(),
+    })
+}
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("sellrod")
+        .description("Sell a rod from your inventory back to the shop")
+        .dm_permission(false)
+        .add_option(CreateCommandOption::new(CommandOptionType::Integer, "rod",
+                                             "The inventory number of the rod to sell")
+            .min_int_value(1)
+            .required(true))
+}
@@ -1,36 +1,47 @@
 use serenity::all::{Colour, CommandInteraction, Context, CreateCommand, CreateEmbed, CreateEmbedFooter, CreateInteractionResponse,
                     CreateInteractionResponseMessage};
-use crate::data::multipliers::MultiplierData;
-use crate::data::rods::RodData;
-use crate::nay;
+use crate::data::registry::GameData;
+use crate::data::shop::ROD_STOCK;
+use crate::error::AnglerError;
 
-pub async fn run(ctx: &Context, cmd: &CommandInteraction) {
-    let shop = crate::data::shop::Shop::load();
-    let rod_data = RodData::load();
+pub async fn run(ctx: &Context, cmd: &CommandInteraction, game_data: &GameData) -> Result<(), AnglerError> {
+    let rod_data = &game_data.rods;
+    let shop = crate::data::shop::Shop::load(rod_data, &game_data.bait)?;
 
-    let multiplier = MultiplierData::load();
+    let multiplier = &game_data.multipliers;
 
     let mut fields = Vec::new();
 
     for x in 0..shop.rods.len() {
         let item = &shop.rods[x];
 
-        let base_rod = rod_data.get_base_by_name(item).unwrap();
+        let base_rod = rod_data.get_base_by_name(item)
+            .ok_or_else(|| AnglerError::UnknownRod(item.clone()))?;
 
-        let mut cost = base_rod.cost;
+        let cost = base_rod.get_cost(multiplier);
 
-        if multiplier.shop_discount != 0.0 {
-            cost -= cost * multiplier.shop_discount;
-        }
+        let level = if base_rod.required_level > 1 {
+            format!("\nRequires level {}", base_rod.required_level)
+        } else {
+            "".to_string()
+        };
 
         fields.push((format!("{}: {}", x + 1, base_rod.name),
-                     format!("${}\nRarity: {}", cost, base_rod.rarity), false));
+                     format!("${}\nRarity: {}{}", cost, base_rod.rarity, level), false));
+    }
+
+    for (x, item) in shop.bait.iter().enumerate() {
+        let bait = game_data.bait.get_by_name(item.as_str())
+            .ok_or_else(|| AnglerError::UnknownBait(item.clone()))?;
+
+        fields.push((format!("{}: {} (x{})", ROD_STOCK + x + 1, bait.name, bait.quantity),
+                     format!("${}\n{}", bait.cost, bait.effects()), false));
     }
 
     // create the embedded message
     let embed = CreateEmbed::new()
         .title("Fishing Shop")
-        .description("Run `/buy <#>` to buy an item from the shop!\nRun `/rod #` to view information about a rod\n**Today's Stock:**")
+        .description("Run `/buy <#>` to buy an item from the shop!\nRun `/rod #` to view information about a rod\nRun `/fish bait:<name>` to use bait\n**Today's Stock:**")
         .fields(fields)
         .footer(CreateEmbedFooter::new(format!("Next restock in: {}", shop.get_time_until_restock())))
         .color(Colour::DARK_GOLD);
@@ -40,9 +51,8 @@ pub async fn run(ctx: &Context, cmd: &CommandInteraction) {
         .embed(embed));
 
     // send the message
-    if let Err(err) = cmd.create_response(&ctx.http, builder).await {
-        nay!("Failed to respond to command: {}", err)
-    }
+    cmd.create_response(&ctx.http, builder).await?;
+    Ok(())
 }
 
 pub fn register() -> CreateCommand {
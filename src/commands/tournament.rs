@@ -0,0 +1,182 @@
+use std::sync::Arc;
+use std::time::Duration;
+use chrono::Local;
+use serenity::all::{ChannelId, Colour, CommandInteraction, Context, CreateCommand, CreateEmbed, CreateEmbedFooter,
+                    CreateInteractionResponse, CreateInteractionResponseMessage, CreateMessage, GuildId, Http,
+                    ResolvedOption, ResolvedValue, UserId};
+use crate::data::guildfile::modify_guild;
+use crate::data::registry::game_data;
+use crate::data::tournament::{Scoring, Tournament};
+use crate::data::userfile::{modify_user, store};
+use crate::error::AnglerError;
+use crate::{nay, say, yay};
+
+/// Prize pool used when the admin doesn't give one
+const DEFAULT_PRIZE_POOL: u32 = 1000;
+
+/// The biggest prize pool an admin can set
+pub const MAX_PRIZE_POOL: u32 = 10_000_000;
+
+/// How many places the standings show
+const STANDINGS_SIZE: usize = 10;
+
+fn standings_embed(tournament: &Tournament, title: &str) -> CreateEmbed {
+    let payouts = tournament.payouts();
+
+    let lines = tournament.standings().into_iter().take(STANDINGS_SIZE).enumerate()
+        .map(|(place, (id, score))| {
+            let prize = payouts.get(place).map(|(_, prize)| format!(" (${})", prize)).unwrap_or_default();
+            format!("**#{}** <@{}> - {}{}", place + 1, id, tournament.scoring.format(score), prize)
+        })
+        .collect::<Vec<String>>();
+
+    let description = if lines.is_empty() {
+        "Nobody has landed a fish that counts yet!".to_string()
+    } else {
+        lines.join("\n")
+    };
+
+    CreateEmbed::new()
+        .title(title)
+        .description(description)
+        .fields(vec![
+            ("Scoring:", tournament.scoring.to_string(), true),
+            ("Species:", tournament.species.clone().unwrap_or("Any".to_string()), true),
+            ("Prize pool:", format!("${}", tournament.prize_pool), true),
+        ])
+        .footer(CreateEmbedFooter::new(format!("{} anglers entered", tournament.entries.len())))
+        .color(Colour::ORANGE)
+}
+
+/// Live standings for the guild's tournament
+pub async fn run(ctx: &Context, cmd: &CommandInteraction, guild: &GuildId) -> Result<(), AnglerError> {
+    let builder = match store().read_guild(guild.get()).tournament {
+        Some(tournament) => {
+            let embed = standings_embed(&tournament, "Tournament Standings")
+                .field("Ends:", format!("<t:{}:R>", tournament.ends_at), false);
+            CreateInteractionResponseMessage::new().embed(embed)
+        }
+        None => CreateInteractionResponseMessage::new().content("There's no tournament running right now!"),
+    };
+
+    cmd.create_response(&ctx.http, CreateInteractionResponse::Message(builder)).await?;
+    Ok(())
+}
+
+/// Starts a tournament in the channel the admin ran the command from
+pub async fn start(options: &[ResolvedOption<'_>], ctx: &Context, cmd: &CommandInteraction) -> Result<String, AnglerError> {
+    let Some(guild_id) = cmd.guild_id else {
+        return Ok("Tournaments can only be run in a server!".to_string());
+    };
+    let game_data = game_data(ctx).await;
+
+    let mut minutes = 0;
+    let mut scoring = Scoring::Heaviest;
+    let mut species = None;
+    let mut prize_pool = DEFAULT_PRIZE_POOL;
+    for option in options {
+        match (option.name, &option.value) {
+            ("minutes", ResolvedValue::Integer(value)) => minutes = *value,
+            ("scoring", ResolvedValue::String(id)) => scoring = Scoring::from_id(id)
+                .ok_or_else(|| AnglerError::InvalidOption(format!("{} isn't a scoring mode!", id)))?,
+            ("species", ResolvedValue::String(name)) => species = Some(game_data.fish.fish.iter()
+                .find(|fish| fish.name.eq_ignore_ascii_case(name))
+                .ok_or_else(|| AnglerError::UnknownFish(name.to_string()))?.name.clone()),
+            ("prize", ResolvedValue::Integer(value)) => prize_pool = u32::try_from(*value).unwrap_or(0).min(MAX_PRIZE_POOL),
+            _ => {}
+        }
+    }
+
+    let tournament = Tournament::new(cmd.channel_id.get(), Local::now().timestamp(), minutes, scoring, species, prize_pool);
+    let started = modify_guild(guild_id.get(), |guild| {
+        if guild.tournament.is_some() {
+            return false;
+        }
+        guild.tournament = Some(tournament.clone());
+        true
+    });
+    if !started {
+        return Ok("A tournament is already running! End it with `/admin endtournament` first.".to_string());
+    }
+
+    say!("{} started a {} minute {} tournament in guild {}", cmd.user.name, minutes, scoring, guild_id);
+
+    let embed = standings_embed(&tournament, "A fishing tournament has begun!")
+        .description(format!("Every fish landed with `/fish` counts until <t:{}:t>. Check the standings with `/tournament`.",
+                             tournament.ends_at));
+    if let Err(e) = cmd.channel_id.send_message(&ctx.http, CreateMessage::new().embed(embed)).await {
+        nay!("Failed to announce tournament: {}", e);
+    }
+
+    schedule_end(ctx.http.clone(), guild_id, &tournament);
+    Ok(format!("Tournament started! It ends in {} minutes.", minutes))
+}
+
+/// Ends the guild's tournament early, paying out the standings so far
+pub async fn end(ctx: &Context, cmd: &CommandInteraction) -> Result<String, AnglerError> {
+    let Some(guild_id) = cmd.guild_id else {
+        return Ok("Tournaments can only be run in a server!".to_string());
+    };
+
+    let Some(tournament) = store().read_guild(guild_id.get()).tournament else {
+        return Ok("There's no tournament running right now!".to_string());
+    };
+
+    finish(&ctx.http, guild_id, tournament.started_at).await;
+    Ok("Tournament ended!".to_string())
+}
+
+/// Pays out and posts the results of the guild's tournament, unless it has already been finished
+async fn finish(http: &Http, guild_id: GuildId, started_at: i64) {
+    let tournament = modify_guild(guild_id.get(), |guild| {
+        match &guild.tournament {
+            Some(tournament) if tournament.started_at == started_at => guild.tournament.take(),
+            _ => None,
+        }
+    });
+    let Some(tournament) = tournament else {
+        return;
+    };
+
+    let payouts = tournament.payouts();
+    for (id, prize) in &payouts {
+        modify_user(guild_id.get(), &UserId::new(*id), |user| user.earn(*prize));
+    }
+    yay!("Tournament in guild {} finished, paid out {:?}", guild_id, payouts);
+
+    let mut message = CreateMessage::new().embed(standings_embed(&tournament, "Tournament Results"));
+    if let Some((winner, prize)) = payouts.first() {
+        message = message.content(format!(":trophy: <@{}> wins the tournament and ${}!", winner, prize));
+    }
+    if let Err(e) = ChannelId::new(tournament.channel_id).send_message(http, message).await {
+        nay!("Failed to post tournament results: {}", e);
+    }
+}
+
+/// Waits for a tournament to end, then finishes it
+fn schedule_end(http: Arc<Http>, guild_id: GuildId, tournament: &Tournament) {
+    let started_at = tournament.started_at;
+    let remaining = (tournament.ends_at - Local::now().timestamp()).max(0) as u64;
+
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_secs(remaining)).await;
+        finish(&http, guild_id, started_at).await;
+    });
+}
+
+/// Picks up the tournaments that were running when the bot last shut down.
+/// Ones that ended while it was offline finish right away.
+pub async fn restore_tournaments(http: Arc<Http>) {
+    for guild_id in store().guilds() {
+        if let Some(tournament) = store().read_guild(guild_id).tournament {
+            yay!("Restoring tournament in guild {}", guild_id);
+            schedule_end(http.clone(), GuildId::new(guild_id), &tournament);
+        }
+    }
+}
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("tournament")
+        .description("View the standings of the server's fishing tournament")
+        .dm_permission(false)
+}
@@ -0,0 +1,185 @@
+use std::time::Duration;
+use serenity::all::{ButtonStyle, Colour, CommandInteraction, CommandOptionType, Context, CreateActionRow, CreateButton,
+                    CreateCommand, CreateCommandOption, CreateEmbed, CreateEmbedFooter, CreateInteractionResponse,
+                    CreateInteractionResponseMessage, EditInteractionResponse, GuildId, Mentionable, ResolvedOption,
+                    ResolvedValue, User, UserId};
+use crate::data::registry::GameData;
+use crate::data::userfile::{modify_users, store, UserValues};
+use crate::engine::trade::{trade, Offer, TradeError};
+use crate::error::AnglerError;
+use crate::{nay, say};
+
+/// How long both sides have to confirm before the offer lapses
+const TRADE_TIMEOUT: Duration = Duration::from_secs(120);
+
+const CONFIRM_ID: &str = "trade:confirm";
+const CANCEL_ID: &str = "trade:cancel";
+
+/// Builds one side of the trade from the items named in the command, as they are in `user`'s profile right now
+fn build_offer(user: &UserValues, money: u32, fish: Option<&str>, rod: Option<&str>) -> Result<Offer, AnglerError> {
+    let mut offer = Offer { money, ..Offer::default() };
+
+    if let Some(species) = fish {
+        // the freshest fish of the species, since that's the one worth the most
+        let cooled = user.cooler.iter()
+            .filter(|cooled| cooled.species.eq_ignore_ascii_case(species))
+            .max_by_key(|cooled| cooled.caught_at)
+            .ok_or_else(|| AnglerError::InvalidOption(format!("There's no {} in that cooler!", species)))?;
+        offer.fish.push(cooled.clone());
+    }
+
+    if let Some(name) = rod {
+        let owned = user.rods.iter()
+            .find(|owned| owned.to_string().eq_ignore_ascii_case(name))
+            .ok_or_else(|| AnglerError::InvalidOption(format!("There's no {} in that inventory!", name)))?;
+        offer.rods.push(owned.clone());
+    }
+
+    Ok(offer)
+}
+
+fn trade_embed(sender: &User, partner: &User, sender_offer: &Offer, partner_offer: &Offer,
+               confirmed: &[UserId], status: &str) -> CreateEmbed {
+    let check = |id: UserId| if confirmed.contains(&id) { " :white_check_mark:" } else { "" };
+
+    CreateEmbed::new()
+        .title("Trade Offer")
+        .description(status)
+        .fields(vec![
+            (format!("{} gives{}", sender.name, check(sender.id)), sender_offer.to_string(), true),
+            (format!("{} gives{}", partner.name, check(partner.id)), partner_offer.to_string(), true),
+        ])
+        .footer(CreateEmbedFooter::new("Money sent in a trade is subject to the transfer tax"))
+        .color(Colour::DARK_GREEN)
+}
+
+fn buttons(disabled: bool) -> Vec<CreateActionRow> {
+    vec![CreateActionRow::Buttons(vec![
+        CreateButton::new(CONFIRM_ID)
+            .label("Confirm")
+            .style(ButtonStyle::Success)
+            .disabled(disabled),
+        CreateButton::new(CANCEL_ID)
+            .label("Cancel")
+            .style(ButtonStyle::Danger)
+            .disabled(disabled),
+    ])]
+}
+
+pub async fn run(options: &[ResolvedOption<'_>], ctx: &Context, cmd: &CommandInteraction, sender: &User, guild: &GuildId,
+                 game_data: &GameData) -> Result<(), AnglerError> {
+    let mut partner = None;
+    let (mut give_money, mut want_money) = (0, 0);
+    let (mut give_fish, mut want_fish, mut give_rod, mut want_rod) = (None, None, None, None);
+    for option in options {
+        match (option.name, &option.value) {
+            ("user", ResolvedValue::User(user, _)) => partner = Some(*user),
+            // registered with a u32 maximum, anything bigger is more than anyone has
+            ("give_money", ResolvedValue::Integer(value)) => give_money = u32::try_from(*value).unwrap_or(u32::MAX),
+            ("want_money", ResolvedValue::Integer(value)) => want_money = u32::try_from(*value).unwrap_or(u32::MAX),
+            ("give_fish", ResolvedValue::String(value)) => give_fish = Some(*value),
+            ("want_fish", ResolvedValue::String(value)) => want_fish = Some(*value),
+            ("give_rod", ResolvedValue::String(value)) => give_rod = Some(*value),
+            ("want_rod", ResolvedValue::String(value)) => want_rod = Some(*value),
+            _ => {}
+        }
+    }
+
+    let Some(partner) = partner else {
+        return Err(AnglerError::InvalidOption("Who are you trading with?".to_string()));
+    };
+    if partner.id == sender.id {
+        return Err(AnglerError::InvalidOption("You can't trade with yourself!".to_string()));
+    }
+    if partner.bot {
+        return Err(AnglerError::InvalidOption("Bots don't go fishing!".to_string()));
+    }
+
+    let sender_values = store().read(&sender.id, guild.get());
+    let partner_values = store().read(&partner.id, guild.get());
+    if sender_values.cast || partner_values.cast {
+        return Err(AnglerError::InvalidOption(TradeError::Casting.to_string()));
+    }
+
+    let sender_offer = build_offer(&sender_values, give_money, give_fish, give_rod)?;
+    let partner_offer = build_offer(&partner_values, want_money, want_fish, want_rod)?;
+    if sender_offer.is_empty() && partner_offer.is_empty() {
+        return Err(AnglerError::InvalidOption("A trade needs something in it!".to_string()));
+    }
+
+    let mut confirmed = Vec::new();
+    let status = format!("{} wants to trade with {}! Both of you need to confirm.", sender.mention(), partner.mention());
+    let builder = CreateInteractionResponseMessage::new()
+        .embed(trade_embed(sender, partner, &sender_offer, &partner_offer, &confirmed, status.as_str()))
+        .components(buttons(false));
+    cmd.create_response(&ctx.http, CreateInteractionResponse::Message(builder)).await?;
+    let message = cmd.get_response(&ctx.http).await?;
+
+    let status = loop {
+        let Some(press) = message.await_component_interaction(ctx).timeout(TRADE_TIMEOUT).await else {
+            break "The trade offer expired.".to_string();
+        };
+
+        if press.user.id != sender.id && press.user.id != partner.id {
+            let reply = CreateInteractionResponseMessage::new().content("This isn't your trade!").ephemeral(true);
+            press.create_response(&ctx.http, CreateInteractionResponse::Message(reply)).await?;
+            continue;
+        }
+
+        if press.data.custom_id == CANCEL_ID {
+            press.create_response(&ctx.http, CreateInteractionResponse::Acknowledge).await?;
+            break format!("{} cancelled the trade.", press.user.mention());
+        }
+
+        if !confirmed.contains(&press.user.id) {
+            confirmed.push(press.user.id);
+        }
+
+        if confirmed.len() < 2 {
+            let status = format!("{} confirmed! Waiting on the other side.", press.user.mention());
+            let update = CreateInteractionResponseMessage::new()
+                .embed(trade_embed(sender, partner, &sender_offer, &partner_offer, &confirmed, status.as_str()));
+            press.create_response(&ctx.http, CreateInteractionResponse::UpdateMessage(update)).await?;
+            continue;
+        }
+
+        press.create_response(&ctx.http, CreateInteractionResponse::Acknowledge).await?;
+        let result = modify_users(guild.get(), &sender.id, &partner.id, |sender_values, partner_values| {
+            trade(sender_values, &sender_offer, partner_values, &partner_offer, game_data)
+        });
+        break match result {
+            Ok(tax) => {
+                say!("{} traded [{}] to {} for [{}] (${} tax)", sender.name, sender_offer.to_string().replace('\n', ", "),
+                    partner.name, partner_offer.to_string().replace('\n', ", "), tax);
+                "Trade complete!".to_string()
+            }
+            Err(e) => format!("The trade failed: {}", e),
+        };
+    };
+
+    let edit = EditInteractionResponse::new()
+        .embed(trade_embed(sender, partner, &sender_offer, &partner_offer, &confirmed, status.as_str()))
+        .components(buttons(true));
+    if let Err(e) = cmd.edit_response(&ctx.http, edit).await {
+        nay!("Failed to update trade message: {}", e);
+    }
+    Ok(())
+}
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("trade")
+        .description("Offer another player a trade of money, fish or rods")
+        .dm_permission(false)
+        .add_option(CreateCommandOption::new(CommandOptionType::User, "user", "Who to trade with")
+            .required(true))
+        .add_option(CreateCommandOption::new(CommandOptionType::Integer, "give_money", "Money you give")
+            .min_int_value(1)
+            .max_int_value(u32::MAX as u64))
+        .add_option(CreateCommandOption::new(CommandOptionType::String, "give_fish", "A species from your cooler"))
+        .add_option(CreateCommandOption::new(CommandOptionType::String, "give_rod", "A rod from your inventory, by name"))
+        .add_option(CreateCommandOption::new(CommandOptionType::Integer, "want_money", "Money you want in return")
+            .min_int_value(1)
+            .max_int_value(u32::MAX as u64))
+        .add_option(CreateCommandOption::new(CommandOptionType::String, "want_fish", "A species from their cooler"))
+        .add_option(CreateCommandOption::new(CommandOptionType::String, "want_rod", "A rod from their inventory, by name"))
+}
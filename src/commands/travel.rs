@@ -0,0 +1,53 @@
+use chrono::Local;
+use serenity::all::{CommandOptionType, CreateCommand, CreateCommandOption, GuildId, ResolvedOption, ResolvedValue, User};
+use crate::data::locations::LocationData;
+use crate::data::registry::GameData;
+use crate::data::userfile::{modify_user, store};
+use crate::engine::travel::travel;
+use crate::say;
+
+/// Where the user is and everywhere they could go
+fn list_locations(sender: &User, guild: &GuildId, game_data: &GameData) -> String {
+    let user = store().read(&sender.id, guild.get());
+    let current = game_data.locations.current(user.location.as_deref());
+
+    let lines = game_data.locations.locations.iter().map(|location| {
+        let here = if location.name == current.name { " (you are here)" } else { "" };
+        format!("- **{}**{}: ${}, rods reaching {}ft+, fishes down to {}ft. {}",
+                location, here, location.travel_cost, location.min_depth, location.max_depth, location.description)
+    }).collect::<Vec<String>>();
+
+    format!("You're fishing at the **{}**. Use `/travel` with a location to go somewhere else:\n{}", current, lines.join("\n"))
+}
+
+pub fn run(options: &[ResolvedOption], sender: &User, guild: &GuildId, game_data: &GameData) -> String {
+    let destination = options.iter().find_map(|option| match option {
+        ResolvedOption { name: "location", value: ResolvedValue::String(name), .. } => Some(*name),
+        _ => None,
+    });
+
+    let Some(destination) = destination else {
+        return list_locations(sender, guild, game_data);
+    };
+
+    let now = Local::now().timestamp();
+    match modify_user(guild.get(), &sender.id, |user| travel(user, destination, game_data, now)) {
+        Ok(location) => {
+            say!("{} travelled to the {} for ${}", sender.name, location, location.travel_cost);
+            format!("You travelled to the **{}** for ${}! {}", location, location.travel_cost, location.description)
+        }
+        Err(e) => e.to_string(),
+    }
+}
+
+pub fn register(location_data: &LocationData) -> CreateCommand {
+    let mut location = CreateCommandOption::new(CommandOptionType::String, "location", "Where to go, leave empty to see every location");
+    for spot in &location_data.locations {
+        location = location.add_string_choice(format!("{} (${})", spot.name, spot.travel_cost), spot.name.clone());
+    }
+
+    CreateCommand::new("travel")
+        .description("Travel to another fishing spot")
+        .dm_permission(false)
+        .add_option(location)
+}
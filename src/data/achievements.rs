@@ -0,0 +1,103 @@
+use std::fmt::{Display, Formatter};
+use serde::{Deserialize, Serialize};
+use crate::data::registry::GameData;
+use crate::data::shop::RodRarity;
+use crate::data::userfile::UserValues;
+use crate::error::AnglerError;
+
+fn one() -> u32 {
+    1
+}
+
+/// What a player has to do to unlock an achievement
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Trigger {
+    FishCaught { count: u32 },
+    /// Catch `count` fish of a `FishRarity`
+    CatchRarity {
+        rarity: String,
+        #[serde(default = "one")]
+        count: u32,
+    },
+    /// Catch every species in fish.json
+    Bestiary,
+    /// Have the event with this id from events.json happen `count` times
+    Events { event: String, count: u32 },
+    LineBreaks { count: u32 },
+    /// Own a rod of this `RodRarity` or rarer
+    OwnRod { rarity: String },
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Reward {
+    #[serde(default)]
+    pub money: u32,
+    /// A cosmetic title shown on `/info`
+    #[serde(default)]
+    pub title: Option<String>,
+}
+
+impl Display for Reward {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match (self.money, &self.title) {
+            (0, None) => write!(f, "Bragging rights"),
+            (money, None) => write!(f, "${}", money),
+            (0, Some(title)) => write!(f, "The title \"{}\"", title),
+            (money, Some(title)) => write!(f, "${} and the title \"{}\"", money, title),
+        }
+    }
+}
+
+/// An achievement from achievements.json
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Achievement {
+    /// Stored in userfiles, so this should never change once released
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub trigger: Trigger,
+    #[serde(default)]
+    pub reward: Reward,
+}
+
+impl Achievement {
+    /// How far the player is towards unlocking this, as (progress, goal)
+    pub fn progress(&self, user: &UserValues, game_data: &GameData) -> (u32, u32) {
+        match &self.trigger {
+            Trigger::FishCaught { count } => (user.fish_caught, *count),
+            Trigger::CatchRarity { rarity, count } =>
+                (user.rarities_caught.get(rarity).copied().unwrap_or(0), *count),
+            Trigger::Bestiary => {
+                let species = &game_data.fish.fish;
+                let seen = species.iter().filter(|fish| user.has_seen.contains(&fish.name)).count();
+                (seen as u32, species.len() as u32)
+            }
+            Trigger::Events { event, count } => (user.events.get(event).copied().unwrap_or(0), *count),
+            Trigger::LineBreaks { count } => (user.line_breaks, *count),
+            Trigger::OwnRod { rarity } => {
+                let wanted = RodRarity::from_string(rarity);
+                let owned = user.rods.iter()
+                    .filter_map(|rod| game_data.rods.get_base_by_name(rod.name.as_str()))
+                    .any(|rod| !wanted.is_rarer(&rod.get_rarity()));
+                (owned as u32, 1)
+            }
+        }
+    }
+
+    pub fn is_met(&self, user: &UserValues, game_data: &GameData) -> bool {
+        let (progress, goal) = self.progress(user, game_data);
+        progress >= goal
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AchievementData {
+    pub achievements: Vec<Achievement>,
+}
+
+impl AchievementData {
+    pub fn load() -> Result<Self, AnglerError> {
+        AnglerError::load_json("./data/achievements.json")
+    }
+}
@@ -0,0 +1,105 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use crate::data::fish::FishRarity;
+use crate::error::AnglerError;
+
+fn one() -> f32 {
+    1.0
+}
+
+/// A bait type from bait.json. Every effect is optional and defaults to doing nothing.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Bait {
+    pub name: String,
+    pub description: String,
+    /// Price of one pack from the shop
+    pub cost: u32,
+    /// How many casts a pack lasts
+    pub quantity: u32,
+    /// Multiplies the roll weight of each named `FishRarity`
+    #[serde(default)]
+    pub rarity_weights: HashMap<String, f32>,
+    /// Species this bait attracts
+    #[serde(default)]
+    pub species: Vec<String>,
+    /// How much more likely the attracted species are to bite than the rest
+    #[serde(default = "one")]
+    pub species_weight: f32,
+    /// Extra depth on top of the rod's
+    #[serde(default)]
+    pub depth: u32,
+    /// Multiplies the time until a bite
+    #[serde(default = "one")]
+    pub catch_time: f32,
+}
+
+impl Bait {
+    pub fn rarity_weight(&self, rarity: &FishRarity) -> f32 {
+        self.rarity_weights.get(rarity.to_string().as_str()).copied().unwrap_or(1.0)
+    }
+
+    pub fn species_weight(&self, species: &str) -> f32 {
+        if self.species.iter().any(|name| name == species) {
+            self.species_weight
+        } else {
+            1.0
+        }
+    }
+
+    /// A one line summary of what the bait does, for the shop
+    pub fn effects(&self) -> String {
+        let mut effects = Vec::new();
+        if !self.rarity_weights.is_empty() {
+            let mut boosted = self.rarity_weights.keys().cloned().collect::<Vec<String>>();
+            boosted.sort();
+            effects.push(format!("More {} fish", boosted.join("/")));
+        }
+        if !self.species.is_empty() {
+            effects.push(format!("Attracts {}", self.species.join(", ")));
+        }
+        if self.depth > 0 {
+            effects.push(format!("+{}ft depth", self.depth));
+        }
+        if self.catch_time != 1.0 {
+            effects.push(format!("{}% bite time", (self.catch_time * 100.0).round() as u32));
+        }
+        effects.join(", ")
+    }
+}
+
+impl Display for Bait {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BaitData {
+    pub bait: Vec<Bait>,
+}
+
+impl BaitData {
+    pub fn load() -> Result<Self, AnglerError> {
+        AnglerError::load_json("./data/bait.json")
+    }
+
+    pub fn get_by_name<S: Into<String>>(&self, name: S) -> Option<&Bait> {
+        let name = name.into();
+        self.bait.iter().find(|bait| bait.name.eq_ignore_ascii_case(name.as_str()))
+    }
+
+    /// Picks `count` different bait types for the shop
+    pub fn generate_stock<R: Rng + ?Sized>(&self, count: usize, rng: &mut R) -> Vec<String> {
+        self.bait.choose_multiple(rng, count).map(|bait| bait.name.clone()).collect()
+    }
+}
+
+/// Some of one bait type in a user's inventory
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BaitStack {
+    pub name: String,
+    pub count: u32,
+}
@@ -0,0 +1,107 @@
+use std::path::Path;
+use std::sync::Mutex;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use crate::engine::cast::Cast;
+use crate::nay;
+
+const CASTS_PATH: &str = "./data/casts.json";
+
+/// Serializes every read-modify-write of the pending casts file
+static CASTS_LOCK: Mutex<()> = Mutex::new(());
+
+/// A cast waiting to resolve, kept on disk so a restart doesn't lose the fish on the line
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PendingCast {
+    pub guild_id: u64,
+    pub user_id: u64,
+    pub channel_id: u64,
+    /// Unix time in milliseconds when the catch resolves
+    pub due: i64,
+    pub cast: Cast,
+}
+
+impl PendingCast {
+    pub fn new(guild_id: u64, user_id: u64, channel_id: u64, cast: Cast) -> Self {
+        Self {
+            guild_id,
+            user_id,
+            channel_id,
+            due: Utc::now().timestamp_millis() + cast.catch_time as i64,
+            cast,
+        }
+    }
+
+    /// Milliseconds left until the catch resolves, or `None` if it's already overdue
+    pub fn remaining(&self) -> Option<u64> {
+        let remaining = self.due - Utc::now().timestamp_millis();
+        (remaining > 0).then_some(remaining as u64)
+    }
+}
+
+fn read_casts() -> Vec<PendingCast> {
+    let path = Path::new(CASTS_PATH);
+    if !path.exists() {
+        return Vec::new();
+    }
+
+    let contents = match std::fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) => {
+            nay!("Failed to read pending casts: {}", e);
+            return Vec::new();
+        }
+    };
+
+    serde_json::from_str(contents.as_str()).unwrap_or_else(|e| {
+        nay!("Failed to parse pending casts, they will be dropped: {}", e);
+        Vec::new()
+    })
+}
+
+fn write_casts(casts: &[PendingCast]) {
+    let serialized = serde_json::to_string(casts).unwrap();
+
+    let tmp_path = format!("{}.tmp", CASTS_PATH);
+    if let Err(e) = std::fs::write(tmp_path.as_str(), serialized) {
+        nay!("Failed to write pending casts: {}", e);
+        return;
+    }
+
+    if let Err(e) = std::fs::rename(tmp_path.as_str(), CASTS_PATH) {
+        nay!("Failed to replace pending casts: {}", e);
+    }
+}
+
+/// Every cast still waiting to resolve
+pub fn pending_casts() -> Vec<PendingCast> {
+    let _guard = CASTS_LOCK.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    read_casts()
+}
+
+/// Records a new cast, replacing any stale one for the same user
+pub fn add_pending_cast(pending: PendingCast) {
+    let _guard = CASTS_LOCK.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+
+    let mut casts = read_casts();
+    casts.retain(|cast| !(cast.guild_id == pending.guild_id && cast.user_id == pending.user_id));
+    casts.push(pending);
+
+    write_casts(&casts);
+}
+
+/// Removes a cast so it can be resolved. Returns `false` if it was already taken,
+/// which means something else is resolving it and the caller must not.
+pub fn take_pending_cast(guild_id: u64, user_id: u64, seed: u64) -> bool {
+    let _guard = CASTS_LOCK.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+
+    let mut casts = read_casts();
+    let before = casts.len();
+    casts.retain(|cast| !(cast.guild_id == guild_id && cast.user_id == user_id && cast.cast.seed == seed));
+    if casts.len() == before {
+        return false;
+    }
+
+    write_casts(&casts);
+    true
+}
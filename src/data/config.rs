@@ -1,22 +1,45 @@
 use serde::{Deserialize, Serialize};
+use crate::data::cooler::CoolerConfig;
+use crate::data::leveling::LevelingConfig;
+use crate::error::AnglerError;
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StorageBackend {
+    #[default]
+    Json,
+    Sqlite,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct StorageConfig {
+    pub backend: StorageBackend,
+    pub sqlite_path: String,
+}
+
+impl Default for StorageConfig {
+    fn default() -> Self {
+        Self {
+            backend: StorageBackend::Json,
+            sqlite_path: "./data/angler.db".to_string(),
+        }
+    }
+}
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Config {
     pub motd: String,
     pub debug_mode: bool,
+    #[serde(default)]
+    pub storage: StorageConfig,
+    #[serde(default)]
+    pub cooler: CoolerConfig,
+    #[serde(default)]
+    pub leveling: LevelingConfig,
 }
 
 impl Config {
-    pub fn load() -> Self {
-        let raw_path = "./data/config.json".to_string();
-        let path = std::path::Path::new(raw_path.as_str());
-
-        if !path.exists() {
-            panic!("Failed to load config: file does not exist");
-        }
-
-        let contents = std::fs::read_to_string(path).unwrap();
-
-        serde_json::from_str(contents.as_str()).unwrap()
+    pub fn load() -> Result<Self, AnglerError> {
+        AnglerError::load_json("./data/config.json")
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,218 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use chrono::{Local, NaiveDate};
+use rand::Rng;
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+use crate::data::fish::{FishData, FishRarity, FishType};
+use crate::data::guildfile::modify_guild;
+use crate::data::locations::LocationData;
+use crate::data::registry::GameData;
+use crate::data::rng::{misc_rng, new_seed};
+use crate::data::rods::{BaseRod, RodData, RodModifier};
+use crate::data::shop::Shop;
+use crate::data::userfile::DEFAULT_ROD;
+use crate::error::AnglerError;
+use crate::say;
+
+/// Rarity contracts as (minimum rarity, fish needed, reward)
+const RARITY_CONTRACTS: [(FishRarity, u32, u32); 3] = [
+    (FishRarity::Uncommon, 5, 150),
+    (FishRarity::Rare, 3, 300),
+    (FishRarity::Elusive, 1, 600),
+];
+
+/// Earnings contracts as (money to earn, reward)
+const EARN_CONTRACTS: [(u32, u32); 3] = [(250, 50), (500, 100), (1000, 200)];
+
+/// Contracts only count shop rods a new angler can buy, so everyone in the guild can finish them
+const MAX_ROD_LEVEL: u32 = 1;
+
+/// The rarest species a species contract asks for, so the target bites often enough to land in a day
+const MAX_SPECIES_RARITY: FishRarity = FishRarity::Rare;
+
+/// Species contracts pay a flat amount plus this many times the species' base value
+const SPECIES_BASE_REWARD: u32 = 100;
+const SPECIES_REWARD_MULTIPLIER: u32 = 3;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ContractKind {
+    /// Land `count` fish of `rarity` or rarer
+    CatchRarity { rarity: FishRarity, count: u32 },
+    /// Land one `species` heavier than `weight`
+    CatchSpecies { species: String, weight: u32 },
+    /// Earn `amount` from selling fish and rods
+    Earn { amount: u32 },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Contract {
+    pub kind: ContractKind,
+    pub reward: u32,
+}
+
+impl Contract {
+    /// The progress needed to complete the contract
+    pub fn goal(&self) -> u32 {
+        match &self.kind {
+            ContractKind::CatchRarity { count, .. } => *count,
+            ContractKind::CatchSpecies { .. } => 1,
+            ContractKind::Earn { amount } => *amount,
+        }
+    }
+}
+
+impl Display for Contract {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.kind {
+            ContractKind::CatchRarity { rarity, count } => write!(f, "Catch {} {} or better fish", count, rarity),
+            ContractKind::CatchSpecies { species, weight } => write!(f, "Land a {} over {}lbs", species, weight),
+            ContractKind::Earn { amount } => write!(f, "Earn ${}", amount),
+        }
+    }
+}
+
+/// A guild's contracts for the day
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContractBoard {
+    /// The shop restock these contracts expire at, which also identifies the day's board
+    pub refresh: i64,
+    /// The seed the contracts were rolled from
+    pub seed: u64,
+    pub contracts: Vec<Contract>,
+}
+
+impl ContractBoard {
+    /// Rolls a board that can be completed with the rods in `shop` a new angler can buy, travelling wherever they reach,
+    /// before the shop restocks after `today`
+    pub fn generate(fish_data: &FishData, rod_data: &RodData, location_data: &LocationData, shop: &Shop, today: NaiveDate,
+                    seed: u64) -> Self {
+        let mut rng = misc_rng(seed);
+
+        // bought rods may roll any modifier, so they're judged by the worst one.
+        // The starter rod is always around unmodified, even if every shop rod is out of reach.
+        let rods = shop.rods.iter()
+            .filter_map(|name| rod_data.get_base_by_name(name))
+            .filter(|rod| rod.required_level <= MAX_ROD_LEVEL)
+            .map(|rod| worst_case(&rod, &rod_data.modifiers))
+            .chain(rod_data.get_base_by_name(DEFAULT_ROD).map(|rod| (rod.depth, rod.weight_limit)))
+            .collect::<Vec<(u32, u32)>>();
+
+        let mut contracts = Vec::new();
+
+        let (rarity, count, reward) = RARITY_CONTRACTS[rng.gen_range(0..RARITY_CONTRACTS.len())].clone();
+        contracts.push(Contract { kind: ContractKind::CatchRarity { rarity, count }, reward });
+
+        if let Some(contract) = species_contract(fish_data, location_data, &rods, today, &mut rng) {
+            contracts.push(contract);
+        }
+
+        let (amount, reward) = EARN_CONTRACTS[rng.gen_range(0..EARN_CONTRACTS.len())];
+        contracts.push(Contract { kind: ContractKind::Earn { amount }, reward });
+
+        Self {
+            refresh: shop.refresh,
+            seed,
+            contracts,
+        }
+    }
+
+    /// The guild's contracts for today, rolling new ones if the shop has restocked since they were made
+    pub fn current(guild_id: u64, game_data: &GameData) -> Result<Self, AnglerError> {
+        let shop = Shop::load(&game_data.rods, &game_data.bait)?;
+
+        Ok(modify_guild(guild_id, |guild| {
+            match &guild.contracts {
+                Some(board) if board.refresh == shop.refresh => board.clone(),
+                _ => {
+                    let seed = new_seed();
+                    say!("Rolling contracts for guild {} (seed: {})", guild_id, seed);
+                    let board = Self::generate(&game_data.fish, &game_data.rods, &game_data.locations, &shop,
+                                               Local::now().date_naive(), seed);
+                    guild.contracts = Some(board.clone());
+                    board
+                }
+            }
+        }))
+    }
+}
+
+/// The depth and weight limit a rod is sure to have, whichever of the `modifiers` it rolls when bought
+fn worst_case(rod: &BaseRod, modifiers: &[RodModifier]) -> (u32, u32) {
+    let depth = modifiers.iter().map(|modifier| modifier.depth).min().unwrap_or(0).min(0);
+    let weight_limit = modifiers.iter().map(|modifier| modifier.weight_limit).min().unwrap_or(0).min(0);
+    (rod.depth.saturating_add_signed(depth), rod.weight_limit.saturating_add_signed(weight_limit))
+}
+
+/// Whether a species bites often enough to ask for: in season, not too rare, and not held back by weather or time of day
+fn is_everyday(fish: &FishType, today: NaiveDate) -> bool {
+    fish.is_active(today) && fish.weather.is_empty() && fish.times.is_empty()
+        && FishRarity::from_str(fish.min_rarity.as_str()).is_ok_and(|rarity| rarity.ident() <= MAX_SPECIES_RARITY.ident())
+}
+
+/// Picks an everyday species one of the rods can reach, and a weight to beat that won't snap that rod's line.
+/// `rods` are each rod's (depth, weight limit).
+fn species_contract<R: Rng + ?Sized>(fish_data: &FishData, location_data: &LocationData, rods: &[(u32, u32)],
+                                     today: NaiveDate, rng: &mut R) -> Option<Contract> {
+    // each species with the heaviest catch a single rod can both reach and hold
+    let mut targets: Vec<(&FishType, u32)> = Vec::new();
+    for &(depth, weight_limit) in rods {
+        for fish in location_data.reachable_fish(fish_data, depth) {
+            let heaviest = fish.max_weight.min(weight_limit);
+            if fish.avg_weight >= heaviest || !is_everyday(fish, today) {
+                continue;
+            }
+            match targets.iter_mut().find(|(target, _)| target.name == fish.name) {
+                Some((_, limit)) => *limit = (*limit).max(heaviest),
+                None => targets.push((fish, heaviest)),
+            }
+        }
+    }
+    let (fish, heaviest) = *targets.choose(rng)?;
+
+    let weight = fish.avg_weight + rng.gen_range(0..=(heaviest - fish.avg_weight) / 2);
+
+    Some(Contract {
+        kind: ContractKind::CatchSpecies { species: fish.name.clone(), weight },
+        reward: SPECIES_BASE_REWARD + fish.value * SPECIES_REWARD_MULTIPLIER,
+    })
+}
+
+/// A user's progress on a guild's contract board
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct ContractProgress {
+    /// The `refresh` of the board this progress is for
+    pub board: i64,
+    pub progress: Vec<u32>,
+    pub claimed: Vec<bool>,
+}
+
+impl ContractProgress {
+    /// Starts over if the progress is for an older board
+    pub fn sync(&mut self, board: &ContractBoard) {
+        if self.board != board.refresh || self.progress.len() != board.contracts.len() {
+            *self = Self {
+                board: board.refresh,
+                progress: vec![0; board.contracts.len()],
+                claimed: vec![false; board.contracts.len()],
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::engine::testing::{game_data, rod};
+    use super::worst_case;
+
+    #[test]
+    fn bought_rods_are_judged_by_their_worst_modifier() {
+        let modifiers = &game_data().rods.modifiers;
+        let base = rod("Fiberglass Casting Rod").base;
+
+        // the Old modifier takes 5 off both
+        assert_eq!(worst_case(&base, modifiers), (base.depth - 5, base.weight_limit - 5));
+        assert_eq!(worst_case(&base, &[]), (base.depth, base.weight_limit));
+    }
+}
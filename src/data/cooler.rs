@@ -0,0 +1,106 @@
+use serde::{Deserialize, Serialize};
+use crate::data::fish::{Fish, FishRarity};
+use crate::data::registry::GameData;
+use crate::data::rods::Rod;
+
+fn one() -> f32 {
+    1.0
+}
+
+/// How much of its value a fish keeps after a number of hours in the cooler
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct FreshnessPoint {
+    pub hours: f32,
+    pub value: f32,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct CoolerConfig {
+    /// How many fish fit in the cooler with a Common rod
+    pub capacity: u32,
+    /// Extra room for each rod tier above Common
+    pub capacity_per_tier: u32,
+    /// Points on the freshness curve, sorted by hours. Values between points are interpolated
+    /// and fish older than the last point keep its value.
+    pub freshness: Vec<FreshnessPoint>,
+}
+
+impl Default for CoolerConfig {
+    fn default() -> Self {
+        Self {
+            capacity: 10,
+            capacity_per_tier: 5,
+            freshness: vec![
+                FreshnessPoint { hours: 0.0, value: 1.0 },
+                FreshnessPoint { hours: 6.0, value: 0.9 },
+                FreshnessPoint { hours: 24.0, value: 0.5 },
+                FreshnessPoint { hours: 72.0, value: 0.2 },
+            ],
+        }
+    }
+}
+
+impl CoolerConfig {
+    /// The share of a fish's value left after `hours`
+    pub fn freshness(&self, hours: f32) -> f32 {
+        let Some(first) = self.freshness.first() else {
+            return 1.0;
+        };
+        if hours <= first.hours {
+            return first.value;
+        }
+
+        for pair in self.freshness.windows(2) {
+            let (from, to) = (&pair[0], &pair[1]);
+            if hours <= to.hours {
+                let progress = (hours - from.hours) / (to.hours - from.hours);
+                return from.value + (to.value - from.value) * progress;
+            }
+        }
+
+        self.freshness.last().map(|point| point.value).unwrap_or(1.0)
+    }
+
+    /// How many fish fit in the cooler while using `rod`
+    pub fn capacity(&self, rod: &Rod) -> usize {
+        (self.capacity + self.capacity_per_tier * rod.base.get_rarity().get_ident() as u32) as usize
+    }
+}
+
+/// A caught fish waiting to be sold
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct CooledFish {
+    pub species: String,
+    pub rarity: FishRarity,
+    pub weight: f32,
+    /// Unix timestamp in seconds
+    pub caught_at: i64,
+    /// Multiplies what the fish sells for, set by events
+    #[serde(default = "one")]
+    pub value_multiplier: f32,
+}
+
+impl CooledFish {
+    pub fn new(fish: &Fish, caught_at: i64) -> Self {
+        Self {
+            species: fish.fish_type.name.clone(),
+            rarity: fish.rarity.clone(),
+            weight: fish.weight,
+            caught_at,
+            value_multiplier: 1.0,
+        }
+    }
+
+    pub fn hours_old(&self, now: i64) -> f32 {
+        (now - self.caught_at).max(0) as f32 / 3600.0
+    }
+
+    /// What the fish sells for at `now`, or `None` if its species is no longer in fish.json
+    pub fn value(&self, game_data: &GameData, now: i64) -> Option<u32> {
+        let fish_type = game_data.fish.fish_type_by_name(self.species.as_str())?;
+        let fresh = fish_type.get_value(self.weight, game_data) * self.rarity.value_multiplier() * self.value_multiplier;
+        let freshness = game_data.config.cooler.freshness(self.hours_old(now));
+
+        Some(((fresh * freshness) as u32).max(1))
+    }
+}
@@ -0,0 +1,126 @@
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use crate::data::fish::{Fish, FishRarity};
+use crate::error::AnglerError;
+
+fn yes() -> bool {
+    true
+}
+
+/// What has to be true of a landed fish for an event to roll. Empty lists allow anything.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EventConditions {
+    /// Only fish of this rarity or rarer
+    #[serde(default)]
+    pub min_rarity: Option<FishRarity>,
+    /// Base rod names the fish has to be caught with
+    #[serde(default)]
+    pub rods: Vec<String>,
+    /// Locations the fish has to be caught at
+    #[serde(default)]
+    pub locations: Vec<String>,
+}
+
+/// What an event does to the catch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Effect {
+    /// The fish is taken before it reaches the cooler
+    Steal,
+    /// The fish sells for this many times its value
+    MultiplyValue { multiplier: f32 },
+    /// Packs of bait added to the player's inventory
+    BonusBait { bait: String, count: u32 },
+    /// Seconds the player has to wait before casting again
+    ExtraCastTime { seconds: u32 },
+}
+
+/// The message posted when an event fires. `{fish}` and `{weight}` in the description are filled in.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventEmbed {
+    pub title: String,
+    pub description: String,
+    /// An image from ./assets to show as the thumbnail
+    #[serde(default)]
+    pub image: Option<String>,
+    /// Hex RGB, e.g. "1F8B4C"
+    pub colour: String,
+}
+
+impl EventEmbed {
+    pub fn colour(&self) -> Option<u32> {
+        u32::from_str_radix(self.colour.trim_start_matches('#'), 16).ok()
+    }
+
+    pub fn describe(&self, fish: &Fish) -> String {
+        self.description
+            .replace("{fish}", fish.to_string().as_str())
+            .replace("{weight}", fish.weight.to_string().as_str())
+    }
+}
+
+/// A random event from events.json, rolled when a fish is landed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Event {
+    /// Used for per-guild settings and player stats, so this should never change once released
+    pub id: String,
+    pub name: String,
+    /// Percent chance the event fires on a fish that meets its conditions
+    pub chance: f32,
+    /// Whether guilds that haven't changed the setting get this event
+    #[serde(default = "yes")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub conditions: EventConditions,
+    pub effects: Vec<Effect>,
+    pub embed: EventEmbed,
+}
+
+impl Event {
+    /// Whether the event can roll for `fish` caught with the base rod `rod` at `location`
+    pub fn applies_to(&self, fish: &Fish, rod: &str, location: Option<&str>) -> bool {
+        let conditions = &self.conditions;
+
+        conditions.min_rarity.as_ref().is_none_or(|rarity| fish.rarity.ident() >= rarity.ident())
+            && (conditions.rods.is_empty() || conditions.rods.iter().any(|name| name == rod))
+            && (conditions.locations.is_empty()
+                || location.is_some_and(|location| conditions.locations.iter().any(|name| name == location)))
+    }
+
+    pub fn steals(&self) -> bool {
+        self.effects.iter().any(|effect| matches!(effect, Effect::Steal))
+    }
+
+    pub fn value_multiplier(&self) -> f32 {
+        self.effects.iter()
+            .map(|effect| match effect {
+                Effect::MultiplyValue { multiplier } => *multiplier,
+                _ => 1.0,
+            })
+            .product()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventData {
+    /// Rolled in order, and only the first to fire happens
+    pub events: Vec<Event>,
+}
+
+impl EventData {
+    pub fn load() -> Result<Self, AnglerError> {
+        AnglerError::load_json("./data/events.json")
+    }
+
+    pub fn get_by_id(&self, id: &str) -> Option<&Event> {
+        self.events.iter().find(|event| event.id == id)
+    }
+
+    /// The events a guild has switched on, given the ones its admins have changed from the default
+    pub fn enabled(&self, overrides: &HashMap<String, bool>) -> Vec<Event> {
+        self.events.iter()
+            .filter(|event| overrides.get(&event.id).copied().unwrap_or(event.enabled))
+            .cloned()
+            .collect()
+    }
+}
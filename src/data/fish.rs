@@ -1,12 +1,22 @@
 use std::fmt::Display;
-use std::path::Path;
 use std::str::FromStr;
+use chrono::NaiveDate;
 use rand::Rng;
+use rand::seq::SliceRandom;
 use serde::{Deserialize, Serialize};
+use crate::data::bait::Bait;
+use crate::data::locations::Location;
 use crate::data::multipliers::MultiplierData;
+use crate::data::registry::GameData;
 use crate::data::rods::Rod;
+use crate::data::seasons::ActiveWindow;
+use crate::data::weather::Conditions;
+use crate::error::AnglerError;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Extra seconds added to a cast for every pound a fish is over its species' average
+pub const WEIGHT_ADD_TIME: f32 = 0.05;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum FishRarity {
     Common, // 40%
     Uncommon, // 30%
@@ -50,9 +60,7 @@ impl FishRarity {
         }
     }
 
-    pub fn weighted_random() -> Self {
-        let mut rng = rand::thread_rng();
-
+    pub fn weighted_random<R: Rng + ?Sized>(rng: &mut R) -> Self {
         let num = rng.gen_range(1..=1000);
 
         let mut current_weight = 0;
@@ -92,6 +100,23 @@ impl FishRarity {
         Self::Common
     }
 
+    /// Rolls a rarity with each weight scaled by `scale`
+    pub fn weighted_random_scaled<R: Rng + ?Sized>(rng: &mut R, scale: impl Fn(&FishRarity) -> f32) -> Self {
+        let rarities = [Self::Common, Self::Uncommon, Self::Rare, Self::Elusive, Self::Legendary, Self::Mythical];
+        let weights = rarities.iter()
+            .map(|rarity| rarity.get_weight() as f32 * scale(rarity))
+            .collect::<Vec<f32>>();
+
+        let mut num = rng.gen_range(0.0..weights.iter().sum::<f32>());
+        for (rarity, weight) in rarities.iter().zip(weights) {
+            if num < weight {
+                return rarity.clone();
+            }
+            num -= weight;
+        }
+        Self::Common
+    }
+
     pub fn from_string<S: Into<String>>(rarity: S) -> Option<Self> {
         let rarity = rarity.into();
         match rarity.as_str() {
@@ -144,23 +169,48 @@ pub struct FishType {
     pub avg_weight: u32,
     pub max_weight: u32,
     pub min_rarity: String,
+    /// Names of the locations this species lives at
+    #[serde(default)]
+    pub habitats: Vec<String>,
+    /// Weather this species only bites in, any weather if empty
+    #[serde(default)]
+    pub weather: Vec<String>,
+    /// Times of day this species only bites at, any time if empty
+    #[serde(default)]
+    pub times: Vec<String>,
+    /// When this species can be caught, all year if empty
+    #[serde(default)]
+    pub active: Vec<ActiveWindow>,
 }
 
 impl FishType {
-    pub fn random_weight(&self) -> f32 {
-        rand::thread_rng().gen_range(self.min_weight as f32..self.max_weight as f32)
+    pub fn lives_in(&self, location: &str) -> bool {
+        self.habitats.iter().any(|habitat| habitat == location)
     }
 
-    pub fn get_value(&self, weight: f32) -> f32 {
-        let fish_data = FishData::load();
+    pub fn is_seasonal(&self) -> bool {
+        !self.active.is_empty()
+    }
 
-        let value_diff = (weight - self.avg_weight as f32) * fish_data.weight_factor;
+    pub fn is_active(&self, date: NaiveDate) -> bool {
+        !self.is_seasonal() || self.active.iter().any(|window| window.is_active(date))
+    }
 
-        let min_value = (self.value / 4) as f32;
+    /// The next day an out of season species comes back, or None if it never will
+    pub fn returns_on(&self, date: NaiveDate) -> Option<NaiveDate> {
+        self.active.iter().filter_map(|window| window.next_start(date)).min()
+    }
+
+    pub fn random_weight<R: Rng + ?Sized>(&self, rng: &mut R) -> f32 {
+        rng.gen_range(self.min_weight as f32..self.max_weight as f32)
+    }
 
-        let multipliers = MultiplierData::load();
+    pub fn get_value(&self, weight: f32, game_data: &GameData) -> f32 {
+        let value_diff = (weight - self.avg_weight as f32) * game_data.fish.weight_factor;
+
+        let min_value = (self.value / 4) as f32;
 
-        (self.value as f32 + value_diff).round().max(min_value) * multipliers.value
+        (self.value as f32 + value_diff).round().max(min_value) * game_data.multipliers.value
     }
 }
 
@@ -177,21 +227,8 @@ pub struct FishData {
 }
 
 impl FishData {
-    pub fn load() -> Self {
-        let raw_path = "./data/fish.json".to_string();
-        let path = Path::new(raw_path.as_str());
-
-        if !path.exists() {
-            panic!("Failed to load rod data: file does not exist");
-        }
-
-        let contents = std::fs::read_to_string(path).unwrap();
-
-        serde_json::from_str(contents.as_str()).unwrap()
-    }
-
-    pub fn get_fish_above_depth(&self, depth: u32) -> Vec<&FishType> {
-        self.fish.iter().filter(|fish| fish.depth <= depth).collect()
+    pub fn load() -> Result<Self, AnglerError> {
+        AnglerError::load_json("./data/fish.json")
     }
 
     pub fn fish_type_by_name<S: Into<String>>(&self, name: S) -> Option<&FishType> {
@@ -200,7 +237,7 @@ impl FishData {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Fish {
     pub fish_type: FishType,
     pub rarity: FishRarity,
@@ -209,15 +246,25 @@ pub struct Fish {
 
 impl Fish {
 
-    pub fn random_fish(fish_data: &FishData, caught_with: &Rod) -> Self {
-        let mut rng = rand::thread_rng();
+    pub fn random_fish<R: Rng + ?Sized>(fish_data: &FishData, caught_with: &Rod, bait: Option<&Bait>,
+                                        location: &Location, conditions: &Conditions, rng: &mut R) -> Self {
+        if bait.is_some() || !conditions.is_calm() {
+            return Self::random_weighted_fish(fish_data, caught_with, bait, location, conditions, rng);
+        }
+
+        Self::random_plain_fish(fish_data, caught_with, location, conditions, rng)
+    }
 
+    fn random_plain_fish<R: Rng + ?Sized>(fish_data: &FishData, caught_with: &Rod, location: &Location,
+                                          conditions: &Conditions, rng: &mut R) -> Self {
         // generate the fish's rarity
-        let rarity = FishRarity::weighted_random();
+        let rarity = FishRarity::weighted_random(rng);
 
-        // generate the fish type based on depth and randomness
+        // generate the fish type based on the location, conditions, depth and randomness
         let fishing_depth = caught_with.get_depth();
-        let fish_above_depth = fish_data.get_fish_above_depth(fishing_depth);
+        let fish_above_depth = location.catchable(fish_data, fishing_depth).into_iter()
+            .filter(|fish| conditions.allows(fish))
+            .collect::<Vec<&FishType>>();
 
         let mut fish_type = fish_above_depth[rng.gen_range(0..fish_above_depth.len())].clone();
 
@@ -227,7 +274,7 @@ impl Fish {
         }
 
         // generate the fish's weight
-        let weight = (fish_type.random_weight() * 10.0).round() / 10.0;
+        let weight = (fish_type.random_weight(rng) * 10.0).round() / 10.0;
 
         Self {
             fish_type,
@@ -236,8 +283,57 @@ impl Fish {
         }
     }
 
-    pub fn get_value(&self) -> u32 {
-        (self.fish_type.get_value(self.weight) * self.rarity.value_multiplier()) as u32
+    /// Like `random_fish`, but the bait and conditions shift the rarity weights and favour some species,
+    /// and the bait reaches deeper
+    fn random_weighted_fish<R: Rng + ?Sized>(fish_data: &FishData, caught_with: &Rod, bait: Option<&Bait>,
+                                             location: &Location, conditions: &Conditions, rng: &mut R) -> Self {
+        let rarity = FishRarity::weighted_random_scaled(rng, |rarity| {
+            bait.map_or(1.0, |bait| bait.rarity_weight(rarity)) * conditions.rarity_weight(rarity)
+        });
+
+        let fishing_depth = caught_with.get_depth() + bait.map_or(0, |bait| bait.depth);
+        let catchable = location.catchable(fish_data, fishing_depth).into_iter()
+            .filter(|fish| conditions.allows(fish))
+            .filter(|fish| FishRarity::from_string(fish.min_rarity.clone())
+                .is_some_and(|min_rarity| min_rarity.ident() <= rarity.ident()))
+            .collect::<Vec<&FishType>>();
+
+        let Ok(fish_type) = catchable.choose_weighted(rng, |fish| {
+            bait.map_or(1.0, |bait| bait.species_weight(&fish.name)) * conditions.species_weight(&fish.name)
+        }) else {
+            return Self::random_plain_fish(fish_data, caught_with, location, conditions, rng);
+        };
+        let fish_type = (*fish_type).clone();
+
+        // generate the fish's weight
+        let weight = (fish_type.random_weight(rng) * 10.0).round() / 10.0;
+
+        Self {
+            fish_type,
+            rarity,
+            weight,
+        }
+    }
+
+    pub fn get_value(&self, game_data: &GameData) -> u32 {
+        (self.fish_type.get_value(self.weight, game_data) * self.rarity.value_multiplier()) as u32
+    }
+
+    /// Seconds until this fish bites, heavier fish taking longer to reel in
+    pub fn catch_time<R: Rng + ?Sized>(&self, rod: &Rod, multipliers: &MultiplierData, bait: Option<&Bait>,
+                                       rng: &mut R) -> f32 {
+        let weight_catch_time_add = (self.weight - self.fish_type.avg_weight as f32) * WEIGHT_ADD_TIME;
+        let time = rod.random_catch_time(multipliers, rng) + weight_catch_time_add;
+
+        match bait {
+            Some(bait) => time * bait.catch_time,
+            None => time,
+        }
+    }
+
+    /// Whether this fish is too heavy for the rod's line
+    pub fn breaks_line(&self, rod: &Rod) -> bool {
+        self.weight > rod.get_weight_limit() as f32
     }
 }
 
@@ -0,0 +1,51 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+use serde::{Deserialize, Serialize};
+use crate::data::contracts::ContractBoard;
+use crate::data::records::Records;
+use crate::data::tournament::Tournament;
+use crate::data::userfile::store;
+
+/// State shared by everyone fishing in a guild.
+/// Every field is defaulted so new ones can be added without a migration.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct GuildValues {
+    /// The server records for each species
+    #[serde(default)]
+    pub records: Records,
+    /// Today's contracts, rolled the first time someone looks after a restock
+    #[serde(default)]
+    pub contracts: Option<ContractBoard>,
+    /// The tournament in progress, until its results are posted
+    #[serde(default)]
+    pub tournament: Option<Tournament>,
+    /// Minutes the guild's clock is ahead of UTC, for the time of day
+    #[serde(default)]
+    pub utc_offset: i32,
+    /// Events the guild's admins have switched on or off, by id. The rest follow events.json.
+    #[serde(default)]
+    pub events: HashMap<String, bool>,
+}
+
+/// guild id -> that guild's lock
+type GuildLocks = Mutex<HashMap<u64, Arc<Mutex<()>>>>;
+
+static GUILD_LOCKS: OnceLock<GuildLocks> = OnceLock::new();
+
+fn guild_lock(guild_id: u64) -> Arc<Mutex<()>> {
+    let mut locks = GUILD_LOCKS.get_or_init(|| Mutex::new(HashMap::new())).lock().unwrap();
+    locks.entry(guild_id).or_default().clone()
+}
+
+/// Like `modify_user`, for the guild's shared state
+pub fn modify_guild<R>(guild_id: u64, modify: impl FnOnce(&mut GuildValues) -> R) -> R {
+    let lock = guild_lock(guild_id);
+    let _guard = lock.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+
+    let store = store();
+    let mut guild_values = store.read_guild(guild_id);
+    let result = modify(&mut guild_values);
+    store.write_guild(guild_id, &guild_values);
+
+    result
+}
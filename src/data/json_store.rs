@@ -0,0 +1,203 @@
+use std::path::Path;
+use serde::Serialize;
+use serenity::all::UserId;
+use chrono::Local;
+use crate::data::guildfile::GuildValues;
+use crate::data::leaderboard::{Category, LeaderboardIndex};
+use crate::data::migrations::{is_too_new, parse_user_values, ProfileError};
+use crate::data::userfile::{UserStore, UserValues};
+use crate::{hey, nay};
+
+const USERFILES_DIR: &str = "./data/guilds/";
+
+/// Writes the profile to a temp file next to `path` and renames it into place,
+/// so a crash mid-write can never leave a truncated userfile behind
+fn write_atomic<T: Serialize>(path: &Path, values: &T) {
+    let serialized = serde_json::to_string(values).unwrap();
+
+    let tmp_path = path.with_extension("json.tmp");
+    if let Err(e) = std::fs::write(&tmp_path, serialized) {
+        nay!("Failed to write userfile {}: {}", tmp_path.display(), e);
+        return;
+    }
+
+    if let Err(e) = std::fs::rename(&tmp_path, path) {
+        nay!("Failed to replace userfile {}: {}", path.display(), e);
+    }
+}
+
+/// Stores each user as its own JSON file under `./data/guilds/<guild>/users/<user>.json`
+pub struct JsonStore {
+    root: String,
+    leaderboards: LeaderboardIndex,
+}
+
+impl JsonStore {
+    pub fn new() -> Self {
+        Self {
+            root: USERFILES_DIR.to_string(),
+            leaderboards: LeaderboardIndex::new(),
+        }
+    }
+
+    pub fn get_userfile_path(&self, id: &UserId, guild_id: u64) -> String {
+        format!("{}{}/users/{}.json", self.root, guild_id, id)
+    }
+
+    pub fn get_guildfile_path(&self, guild_id: u64) -> String {
+        format!("{}{}/guild.json", self.root, guild_id)
+    }
+
+    fn create_userfile(&self, id: &UserId, guild_id: u64) {
+        let raw_path = self.get_userfile_path(id, guild_id);
+        let path = Path::new(raw_path.as_str());
+
+        if !path.exists() {
+            // make the directories
+            if let Err(e) = std::fs::create_dir_all(path.parent().unwrap()) {
+                nay!("Failed to create userfile directories: {}", e);
+                return;
+            }
+        }
+
+        write_atomic(path, &UserValues::default());
+    }
+
+    /// Moves an unreadable userfile aside so it can be inspected later, and replaces it with a fresh profile
+    fn quarantine_userfile(&self, id: &UserId, guild_id: u64, reason: String) -> UserValues {
+        let raw_path = self.get_userfile_path(id, guild_id);
+        let quarantine_path = format!("{}.corrupt-{}", raw_path, Local::now().timestamp());
+
+        hey!("Userfile for {} in guild {} is unreadable ({}), moving it to {} and starting fresh",
+            id, guild_id, reason, quarantine_path);
+
+        if let Err(e) = std::fs::rename(raw_path.as_str(), quarantine_path.as_str()) {
+            nay!("Failed to quarantine userfile {}: {}", raw_path, e);
+        }
+
+        let user_values = UserValues::default();
+        write_atomic(Path::new(raw_path.as_str()), &user_values);
+        self.leaderboards.update(guild_id, id, &user_values);
+        user_values
+    }
+}
+
+impl Default for JsonStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl UserStore for JsonStore {
+    fn read(&self, id: &UserId, guild_id: u64) -> UserValues {
+        let raw_path = self.get_userfile_path(id, guild_id);
+        let path = Path::new(raw_path.as_str());
+
+        if !path.exists() {
+            self.create_userfile(id, guild_id);
+        }
+
+        let contents = match std::fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) => {
+                nay!("Failed to read userfile {}: {}", raw_path, e);
+                return UserValues::default();
+            }
+        };
+
+        match parse_user_values(contents.as_str()) {
+            Ok((user_values, migrated)) => {
+                if migrated {
+                    write_atomic(path, &user_values);
+                }
+                user_values
+            }
+            Err(ProfileError::TooNew(version)) => {
+                nay!("Userfile {} is from a newer build (version {}), leaving it alone until that build is back",
+                    raw_path, version);
+                UserValues::default()
+            }
+            Err(ProfileError::Unreadable(reason)) => self.quarantine_userfile(id, guild_id, reason),
+        }
+    }
+
+    fn write(&self, id: &UserId, user_values: &UserValues, guild_id: u64) {
+        let raw_path = self.get_userfile_path(id, guild_id);
+        let path = Path::new(raw_path.as_str());
+
+        if !path.exists() {
+            self.create_userfile(id, guild_id);
+        }
+        if std::fs::read_to_string(path).is_ok_and(|contents| is_too_new(contents.as_str())) {
+            nay!("Refusing to overwrite userfile {}, it's from a newer build", raw_path);
+            return;
+        }
+
+        write_atomic(path, user_values);
+        self.leaderboards.update(guild_id, id, user_values);
+    }
+
+    fn guilds(&self) -> Vec<u64> {
+        let Ok(guild_paths) = std::fs::read_dir(self.root.as_str()) else {
+            return Vec::new();
+        };
+
+        guild_paths.filter_map(|entry| entry.ok())
+            .filter_map(|entry| entry.file_name().to_str()?.parse::<u64>().ok())
+            .collect()
+    }
+
+    fn users(&self, guild_id: u64) -> Vec<(UserId, UserValues)> {
+        let Ok(user_paths) = std::fs::read_dir(format!("{}{}/users/", self.root, guild_id)) else {
+            return Vec::new();
+        };
+
+        user_paths.filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                let name = entry.file_name();
+                let id = name.to_str()?.strip_suffix(".json")?.parse::<u64>().ok()?;
+                Some(UserId::new(id))
+            })
+            .map(|id| (id, self.read(&id, guild_id)))
+            .collect()
+    }
+
+    fn leaderboard(&self, guild_id: u64, category: Category) -> Vec<(UserId, f64)> {
+        self.leaderboards.ranking(guild_id, category, || self.users(guild_id))
+    }
+
+    fn read_guild(&self, guild_id: u64) -> GuildValues {
+        let raw_path = self.get_guildfile_path(guild_id);
+
+        let Ok(contents) = std::fs::read_to_string(raw_path.as_str()) else {
+            return GuildValues::default();
+        };
+
+        match serde_json::from_str(contents.as_str()) {
+            Ok(guild_values) => guild_values,
+            Err(e) => {
+                let quarantine_path = format!("{}.corrupt-{}", raw_path, Local::now().timestamp());
+                hey!("Guild file for {} is unreadable ({}), moving it to {} and starting fresh", guild_id, e, quarantine_path);
+
+                if let Err(e) = std::fs::rename(raw_path.as_str(), quarantine_path.as_str()) {
+                    nay!("Failed to quarantine guild file {}: {}", raw_path, e);
+                }
+                GuildValues::default()
+            }
+        }
+    }
+
+    fn write_guild(&self, guild_id: u64, guild_values: &GuildValues) {
+        let raw_path = self.get_guildfile_path(guild_id);
+        let path = Path::new(raw_path.as_str());
+
+        if let Some(parent) = path.parent() {
+            if let Err(e) = std::fs::create_dir_all(parent) {
+                nay!("Failed to create guild directory: {}", e);
+                return;
+            }
+        }
+
+        write_atomic(path, guild_values);
+    }
+}
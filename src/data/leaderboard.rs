@@ -0,0 +1,134 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use serenity::all::UserId;
+use crate::data::userfile::UserValues;
+
+/// What a leaderboard ranks players by
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Category {
+    Balance,
+    FishCaught,
+    Species,
+    Heaviest,
+    Earnings,
+}
+
+impl Category {
+    pub const ALL: [Category; 5] = [Category::Balance, Category::FishCaught, Category::Species,
+                                    Category::Heaviest, Category::Earnings];
+
+    /// Used in command options and button ids
+    pub fn id(&self) -> &'static str {
+        match self {
+            Category::Balance => "balance",
+            Category::FishCaught => "caught",
+            Category::Species => "species",
+            Category::Heaviest => "heaviest",
+            Category::Earnings => "earnings",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|category| category.id() == id)
+    }
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            Category::Balance => "Balance",
+            Category::FishCaught => "Fish Caught",
+            Category::Species => "Unique Species",
+            Category::Heaviest => "Heaviest Catch",
+            Category::Earnings => "Lifetime Earnings",
+        }
+    }
+
+    pub fn score(&self, user: &UserValues) -> f64 {
+        match self {
+            Category::Balance => user.money as f64,
+            Category::FishCaught => user.fish_caught as f64,
+            Category::Species => user.has_seen.len() as f64,
+            Category::Heaviest => user.heaviest_catch.as_ref().map(|catch| catch.weight as f64).unwrap_or(0.0),
+            Category::Earnings => user.total_earned as f64,
+        }
+    }
+
+    pub fn format(&self, score: f64) -> String {
+        match self {
+            Category::Balance | Category::Earnings => format!("${}", score),
+            Category::FishCaught => format!("{} fish", score),
+            Category::Species => format!("{} species", score),
+            Category::Heaviest => format!("{}lbs", (score * 10.0).round() / 10.0),
+        }
+    }
+
+    /// The score as an SQLite expression over a stored profile
+    pub fn sql(&self) -> &'static str {
+        match self {
+            Category::Balance => "json_extract(data, '$.money')",
+            Category::FishCaught => "json_extract(data, '$.fish_caught')",
+            Category::Species => "json_array_length(data, '$.has_seen')",
+            Category::Heaviest => "json_extract(data, '$.heaviest_catch.weight')",
+            Category::Earnings => "json_extract(data, '$.total_earned')",
+        }
+    }
+}
+
+/// Sorts scores highest first, breaking ties by user id so pages stay stable, and drops anyone on zero
+pub fn rank(mut scores: Vec<(UserId, f64)>) -> Vec<(UserId, f64)> {
+    scores.retain(|(_, score)| *score > 0.0);
+    scores.sort_by(|(a_id, a), (b_id, b)| b.total_cmp(a).then(a_id.cmp(b_id)));
+    scores
+}
+
+type GuildScores = HashMap<u64, [f64; Category::ALL.len()]>;
+
+/// Every player's scores, kept in memory for the JSON store so a leaderboard doesn't read every userfile.
+/// A guild is loaded on its first leaderboard and kept up to date by every write after that.
+pub struct LeaderboardIndex {
+    guilds: Mutex<HashMap<u64, GuildScores>>,
+}
+
+impl LeaderboardIndex {
+    pub fn new() -> Self {
+        Self {
+            guilds: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn scores(user: &UserValues) -> [f64; Category::ALL.len()] {
+        Category::ALL.map(|category| category.score(user))
+    }
+
+    /// Records a profile change, if its guild has been loaded
+    pub fn update(&self, guild_id: u64, id: &UserId, user: &UserValues) {
+        if let Some(guild) = self.guilds.lock().unwrap().get_mut(&guild_id) {
+            guild.insert(id.get(), Self::scores(user));
+        }
+    }
+
+    /// The ranked scores for a guild, calling `load` to read every profile the first time
+    pub fn ranking(&self, guild_id: u64, category: Category,
+                   load: impl FnOnce() -> Vec<(UserId, UserValues)>) -> Vec<(UserId, f64)> {
+        // load without holding the lock, since reading a profile can write it back
+        if !self.guilds.lock().unwrap().contains_key(&guild_id) {
+            let scores = load().into_iter()
+                .map(|(id, user)| (id.get(), Self::scores(&user)))
+                .collect::<GuildScores>();
+            self.guilds.lock().unwrap().entry(guild_id).or_insert(scores);
+        }
+
+        let guilds = self.guilds.lock().unwrap();
+        let Some(guild) = guilds.get(&guild_id) else {
+            return Vec::new();
+        };
+
+        let index = Category::ALL.iter().position(|c| *c == category).unwrap_or(0);
+        rank(guild.iter().map(|(id, scores)| (UserId::new(*id), scores[index])).collect())
+    }
+}
+
+impl Default for LeaderboardIndex {
+    fn default() -> Self {
+        Self::new()
+    }
+}
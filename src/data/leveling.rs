@@ -0,0 +1,63 @@
+use serde::{Deserialize, Serialize};
+use crate::data::fish::Fish;
+
+/// How XP is earned and how much each level needs
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct LevelingConfig {
+    /// XP for a Common fish, multiplied by the rarity's value multiplier
+    pub catch_xp: f32,
+    /// Extra XP for every pound the fish weighs
+    pub xp_per_lb: f32,
+    /// Bonus XP the first time a species is caught
+    pub first_catch_xp: u32,
+    /// XP needed to go from level 1 to 2
+    pub base_xp: u32,
+    /// Each level needs this many times the XP of the one before
+    pub growth: f32,
+}
+
+impl Default for LevelingConfig {
+    fn default() -> Self {
+        Self {
+            catch_xp: 10.0,
+            xp_per_lb: 0.5,
+            first_catch_xp: 50,
+            base_xp: 100,
+            growth: 1.3,
+        }
+    }
+}
+
+impl LevelingConfig {
+    /// XP earned for landing `fish`
+    pub fn xp_for_catch(&self, fish: &Fish, first_catch: bool) -> u32 {
+        let xp = self.catch_xp * fish.rarity.value_multiplier() + fish.weight * self.xp_per_lb;
+
+        (xp.round() as u32).saturating_add(if first_catch { self.first_catch_xp } else { 0 })
+    }
+
+    /// XP needed to go from `level` to the next
+    pub fn xp_to_next(&self, level: u32) -> u32 {
+        (self.base_xp as f32 * self.growth.powi(level as i32 - 1)).round().max(1.0) as u32
+    }
+
+    /// The level reached with `xp` in total, starting from 1
+    pub fn level(&self, xp: u32) -> u32 {
+        self.progress(xp).0
+    }
+
+    /// The level reached with `xp`, the XP earned into it and the XP it takes to finish it
+    pub fn progress(&self, xp: u32) -> (u32, u32, u32) {
+        let mut level = 1;
+        let mut remaining = xp;
+
+        loop {
+            let needed = self.xp_to_next(level);
+            if remaining < needed {
+                return (level, remaining, needed);
+            }
+            remaining -= needed;
+            level += 1;
+        }
+    }
+}
@@ -0,0 +1,83 @@
+use std::fmt::{Display, Formatter};
+use serde::{Deserialize, Serialize};
+use crate::data::fish::{FishData, FishType};
+use crate::error::AnglerError;
+
+/// A fishing spot from locations.json
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Location {
+    pub name: String,
+    pub description: String,
+    /// How deep a rod has to reach to fish here at all
+    pub min_depth: u32,
+    /// How deep the water goes. Rods that reach further fish no deeper than this.
+    pub max_depth: u32,
+    /// Price of travelling here
+    pub travel_cost: u32,
+}
+
+impl Location {
+    /// Whether a rod that reaches `depth` can fish here
+    pub fn can_fish(&self, depth: u32) -> bool {
+        depth >= self.min_depth
+    }
+
+    /// The species living here that a line dropped to `depth` can reach
+    pub fn catchable<'a>(&self, fish_data: &'a FishData, depth: u32) -> Vec<&'a FishType> {
+        let depth = depth.min(self.max_depth);
+        fish_data.fish.iter()
+            .filter(|fish| fish.depth <= depth && fish.lives_in(self.name.as_str()))
+            .collect()
+    }
+}
+
+impl Display for Location {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocationData {
+    /// Minutes a player has to wait between trips
+    pub travel_cooldown: u32,
+    /// The first location is where everyone starts
+    pub locations: Vec<Location>,
+}
+
+impl LocationData {
+    pub fn load() -> Result<Self, AnglerError> {
+        AnglerError::load_json("./data/locations.json")
+    }
+
+    /// Where new players start, and where anyone ends up if their location is removed from locations.json
+    pub fn home(&self) -> &Location {
+        &self.locations[0]
+    }
+
+    pub fn get_by_name(&self, name: &str) -> Option<&Location> {
+        self.locations.iter().find(|location| location.name.eq_ignore_ascii_case(name))
+    }
+
+    /// The location a player is at, from the name stored in their profile
+    pub fn current(&self, name: Option<&str>) -> &Location {
+        name.and_then(|name| self.get_by_name(name)).unwrap_or(self.home())
+    }
+
+    /// The deepest location a rod reaching `depth` can fish at
+    pub fn deepest_for(&self, depth: u32) -> &Location {
+        self.locations.iter()
+            .filter(|location| location.can_fish(depth))
+            .max_by_key(|location| location.max_depth)
+            .unwrap_or(self.home())
+    }
+
+    /// Every species a rod reaching `depth` can catch somewhere
+    pub fn reachable_fish<'a>(&self, fish_data: &'a FishData, depth: u32) -> Vec<&'a FishType> {
+        fish_data.fish.iter()
+            .filter(|fish| self.locations.iter()
+                .any(|location| location.can_fish(depth) && fish.depth <= depth.min(location.max_depth)
+                    && fish.lives_in(location.name.as_str())))
+            .collect()
+    }
+}
@@ -0,0 +1,183 @@
+use std::fmt::{Display, Formatter};
+use serde_json::{Map, Value};
+use crate::data::contracts::ContractProgress;
+use crate::data::userfile::{UserValues, DEFAULT_ROD};
+
+/// The schema version written by this build.
+/// Bump this and append a step to `MIGRATIONS` whenever `UserValues` changes shape.
+pub const CURRENT_VERSION: u32 = 11;
+
+type Migration = fn(&mut Map<String, Value>);
+
+/// `MIGRATIONS[n]` upgrades a profile from version `n` to version `n + 1`
+const MIGRATIONS: [Migration; CURRENT_VERSION as usize] = [
+    v0_to_v1,
+    v1_to_v2,
+    v2_to_v3,
+    v3_to_v4,
+    v4_to_v5,
+    v5_to_v6,
+    v6_to_v7,
+    v7_to_v8,
+    v8_to_v9,
+    v9_to_v10,
+    v10_to_v11,
+];
+
+/// Profiles written before versioning existed. Fill in anything a hand-edited file may be missing.
+/// Only the fields v1 had are filled, so later steps still see a v1 profile and can derive their own.
+fn v0_to_v1(user: &mut Map<String, Value>) {
+    user.entry("fish_caught").or_insert(Value::from(0));
+    user.entry("money").or_insert(Value::from(0));
+    user.entry("rod_name").or_insert(Value::from(DEFAULT_ROD));
+    user.entry("rod_modifier").or_insert(Value::Null);
+    user.entry("cast").or_insert(Value::from(false));
+    user.entry("has_seen").or_insert(Value::Array(vec![]));
+}
+
+/// The single `rod_name`/`rod_modifier` pair became a rod inventory
+fn v1_to_v2(user: &mut Map<String, Value>) {
+    let name = user.remove("rod_name").unwrap_or_else(|| Value::from(DEFAULT_ROD));
+    let modifier = user.remove("rod_modifier").unwrap_or(Value::Null);
+
+    let mut rod = Map::new();
+    rod.insert("name".to_string(), name);
+    rod.insert("modifier".to_string(), modifier);
+
+    user.insert("rods".to_string(), Value::Array(vec![Value::Object(rod)]));
+    user.insert("equipped".to_string(), Value::from(0));
+}
+
+/// Catches go into a cooler instead of straight to money
+fn v2_to_v3(user: &mut Map<String, Value>) {
+    user.entry("cooler").or_insert(Value::Array(vec![]));
+}
+
+/// Bait inventory
+fn v3_to_v4(user: &mut Map<String, Value>) {
+    user.entry("bait").or_insert(Value::Array(vec![]));
+}
+
+/// XP and levels. Everyone starts from zero rather than guessing from `fish_caught`.
+fn v4_to_v5(user: &mut Map<String, Value>) {
+    user.entry("xp").or_insert(Value::from(0));
+}
+
+/// Leaderboard stats. Money only ever came from selling, so the current balance is a fair floor for earnings.
+fn v5_to_v6(user: &mut Map<String, Value>) {
+    let money = user.get("money").cloned().unwrap_or(Value::from(0));
+    user.entry("total_earned").or_insert(money);
+    user.entry("heaviest_catch").or_insert(Value::Null);
+}
+
+/// Personal records for each species
+fn v6_to_v7(user: &mut Map<String, Value>) {
+    user.entry("records").or_insert(Value::Object(Map::new()));
+}
+
+/// Achievements, and the counters they track. Past line breaks and turtles weren't recorded, so they start at 0.
+fn v7_to_v8(user: &mut Map<String, Value>) {
+    user.entry("rarities_caught").or_insert(Value::Object(Map::new()));
+    user.entry("line_breaks").or_insert(Value::from(0));
+    user.entry("turtle_events").or_insert(Value::from(0));
+    user.entry("achievements").or_insert(Value::Array(vec![]));
+    user.entry("title").or_insert(Value::Null);
+}
+
+/// Daily contracts. The empty progress is reset to the current board the first time it's used.
+fn v8_to_v9(user: &mut Map<String, Value>) {
+    let progress = serde_json::to_value(ContractProgress::default()).unwrap();
+    user.entry("contracts").or_insert(progress);
+}
+
+/// Locations. Everyone starts out at home and free to travel.
+fn v9_to_v10(user: &mut Map<String, Value>) {
+    user.entry("location").or_insert(Value::Null);
+    user.entry("traveled_at").or_insert(Value::from(0));
+}
+
+/// Random events moved into events.json, so the turtle counter becomes one of the per-event counts
+fn v10_to_v11(user: &mut Map<String, Value>) {
+    let mut events = Map::new();
+    if let Some(turtles) = user.remove("turtle_events").filter(|turtles| turtles.as_u64().is_some_and(|count| count > 0)) {
+        events.insert("turtle".to_string(), turtles);
+    }
+    user.entry("events").or_insert(Value::Object(events));
+    user.entry("tangled_until").or_insert(Value::from(0));
+}
+
+/// Why a stored profile couldn't be read
+#[derive(Debug)]
+pub enum ProfileError {
+    /// The profile isn't valid JSON for any version, so it can be quarantined
+    Unreadable(String),
+    /// The profile was written by a newer build. It must be left alone, or rolling back would reset the player.
+    TooNew(u32),
+}
+
+impl Display for ProfileError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProfileError::Unreadable(reason) => write!(f, "{}", reason),
+            ProfileError::TooNew(version) =>
+                write!(f, "profile version {} is newer than this build supports ({})", version, CURRENT_VERSION),
+        }
+    }
+}
+
+/// Whether a stored profile was written by a newer build, which this one must not overwrite
+pub fn is_too_new(contents: &str) -> bool {
+    serde_json::from_str::<Value>(contents).ok()
+        .and_then(|value| value.get("version")?.as_u64())
+        .is_some_and(|version| version > CURRENT_VERSION as u64)
+}
+
+/// Parses a stored profile, running every migration between its version and `CURRENT_VERSION`.
+/// Returns the profile and whether it was upgraded, so callers can persist the new format.
+pub fn parse_user_values(contents: &str) -> Result<(UserValues, bool), ProfileError> {
+    let value: Value = serde_json::from_str(contents).map_err(|e| ProfileError::Unreadable(e.to_string()))?;
+
+    let Value::Object(mut user) = value else {
+        return Err(ProfileError::Unreadable("profile is not a JSON object".to_string()));
+    };
+
+    let version = match user.get("version") {
+        None => 0,
+        Some(version) => version.as_u64()
+            .ok_or_else(|| ProfileError::Unreadable("profile version is not a number".to_string()))?,
+    };
+
+    if version > CURRENT_VERSION as u64 {
+        return Err(ProfileError::TooNew(version.min(u32::MAX as u64) as u32));
+    }
+    let version = version as u32;
+
+    for migration in &MIGRATIONS[version as usize..] {
+        migration(&mut user);
+    }
+    user.insert("version".to_string(), Value::from(CURRENT_VERSION));
+
+    let user_values = serde_json::from_value(Value::Object(user)).map_err(|e| ProfileError::Unreadable(e.to_string()))?;
+
+    Ok((user_values, version < CURRENT_VERSION))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_too_new, parse_user_values, ProfileError, CURRENT_VERSION};
+
+    #[test]
+    fn newer_profiles_are_kept_apart_from_corrupt_ones() {
+        let newer = format!(r#"{{ "version": {}, "money": 100 }}"#, CURRENT_VERSION + 1);
+        assert!(matches!(parse_user_values(newer.as_str()), Err(ProfileError::TooNew(version)) if version == CURRENT_VERSION + 1));
+        assert!(is_too_new(newer.as_str()));
+
+        assert!(matches!(parse_user_values("{ not json"), Err(ProfileError::Unreadable(_))));
+        assert!(!is_too_new("{ not json"));
+
+        let (user, migrated) = parse_user_values(r#"{ "money": 100 }"#).expect("old profiles should migrate");
+        assert!(migrated);
+        assert_eq!(user.money, 100);
+        assert!(!is_too_new(serde_json::to_string(&user).unwrap().as_str()));
+    }
+}
@@ -1,6 +1,26 @@
 pub mod fish;
 pub mod userfile;
+pub mod migrations;
+pub mod json_store;
+pub mod sqlite_store;
 pub mod shop;
 pub mod config;
 pub mod multipliers;
 pub mod rods;
+pub mod registry;
+pub mod validate;
+pub mod rng;
+pub mod casts;
+pub mod cooler;
+pub mod bait;
+pub mod leveling;
+pub mod leaderboard;
+pub mod records;
+pub mod guildfile;
+pub mod achievements;
+pub mod contracts;
+pub mod tournament;
+pub mod locations;
+pub mod weather;
+pub mod seasons;
+pub mod events;
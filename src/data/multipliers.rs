@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use crate::error::AnglerError;
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct MultiplierData {
@@ -7,19 +8,25 @@ pub struct MultiplierData {
     pub catch_chance: u32,
     pub rarity_boost: bool,
     pub shop_discount: f32, // todo
+    /// The share of a rod's shop price paid back by `/sellrod`
+    #[serde(default = "default_resale")]
+    pub resale: f32,
+    /// The share of every `/pay` and traded sum that's taken out of the economy
+    #[serde(default)]
+    pub transfer_tax: f32,
 }
 
-impl MultiplierData {
-    pub fn load() -> Self {
-        let raw_path = "./data/multipliers.json".to_string();
-        let path = std::path::Path::new(raw_path.as_str());
-
-        if !path.exists() {
-            panic!("Failed to load config: file does not exist");
-        }
+fn default_resale() -> f32 {
+    0.5
+}
 
-        let contents = std::fs::read_to_string(path).unwrap();
+impl MultiplierData {
+    /// The tax taken out of a transfer of `amount`, never more than the amount itself
+    pub fn transfer_tax(&self, amount: u32) -> u32 {
+        ((amount as f64 * self.transfer_tax as f64).round() as u32).min(amount)
+    }
 
-        serde_json::from_str(contents.as_str()).unwrap()
+    pub fn load() -> Result<Self, AnglerError> {
+        AnglerError::load_json("./data/multipliers.json")
     }
 }
\ No newline at end of file
@@ -0,0 +1,63 @@
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use crate::data::fish::Fish;
+
+/// One record-setting catch
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct CatchRecord {
+    /// The user who caught it
+    pub holder: u64,
+    pub weight: f32,
+    pub value: u32,
+    /// Unix timestamp in seconds
+    pub caught_at: i64,
+}
+
+/// The best catches of a single species
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct SpeciesRecord {
+    pub heaviest: Option<CatchRecord>,
+    pub most_valuable: Option<CatchRecord>,
+}
+
+/// Species name -> its records
+pub type Records = HashMap<String, SpeciesRecord>;
+
+/// Records the catch against `records` if it beats the heaviest or most valuable of its species.
+/// Returns whether an existing record was broken. The first catch of a species sets its records without breaking any.
+pub fn record_catch(records: &mut Records, holder: u64, fish: &Fish, value: u32, caught_at: i64) -> bool {
+    let record = CatchRecord { holder, weight: fish.weight, value, caught_at };
+    let species = records.entry(fish.fish_type.name.clone()).or_default();
+    let first = species.heaviest.is_none() && species.most_valuable.is_none();
+
+    let heaviest = species.heaviest.as_ref().is_none_or(|best| record.weight > best.weight);
+    if heaviest {
+        species.heaviest = Some(record.clone());
+    }
+
+    let most_valuable = species.most_valuable.as_ref().is_none_or(|best| record.value > best.value);
+    if most_valuable {
+        species.most_valuable = Some(record);
+    }
+
+    !first && (heaviest || most_valuable)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{record_catch, Records};
+    use crate::engine::testing::fish;
+
+    #[test]
+    fn only_beaten_records_count_as_broken() {
+        let mut records = Records::new();
+
+        assert!(!record_catch(&mut records, 1, &fish("Trout", 10.0), 20, 0), "the first catch sets the record");
+        assert!(!record_catch(&mut records, 2, &fish("Trout", 5.0), 10, 1), "a smaller catch breaks nothing");
+        assert!(record_catch(&mut records, 3, &fish("Trout", 12.0), 15, 2), "a heavier catch breaks the record");
+
+        let trout = &records["Trout"];
+        assert_eq!(trout.heaviest.as_ref().map(|record| record.holder), Some(3));
+        assert_eq!(trout.most_valuable.as_ref().map(|record| record.holder), Some(1));
+    }
+}
@@ -0,0 +1,120 @@
+use std::sync::{Arc, RwLock};
+use serenity::all::Context;
+use serenity::prelude::TypeMapKey;
+use crate::data::achievements::AchievementData;
+use crate::data::bait::BaitData;
+use crate::data::config::Config;
+use crate::data::events::EventData;
+use crate::data::fish::FishData;
+use crate::data::locations::LocationData;
+use crate::data::multipliers::MultiplierData;
+use crate::data::rods::RodData;
+use crate::data::weather::WeatherData;
+use crate::data::validate::{validate, validate_achievements, validate_bait, validate_config, validate_events,
+                            validate_locations, validate_multipliers, validate_weather, Issue, Severity};
+use crate::error::AnglerError;
+use crate::hey;
+
+/// Every static game data file, parsed and validated once.
+/// Handlers take a snapshot with `game_data` and pass it down, so a reload never changes data mid-command.
+#[derive(Debug, Clone)]
+pub struct GameData {
+    pub config: Config,
+    pub fish: FishData,
+    pub rods: RodData,
+    pub multipliers: MultiplierData,
+    pub bait: BaitData,
+    pub achievements: AchievementData,
+    pub locations: LocationData,
+    pub weather: WeatherData,
+    pub events: EventData,
+}
+
+impl GameData {
+    /// Loads every data file from disk and rejects the set if any of them are invalid
+    pub fn load() -> Result<Self, AnglerError> {
+        let game_data = Self::read()?;
+
+        let errors = game_data.validate();
+        if !errors.is_empty() {
+            return Err(AnglerError::InvalidData(errors));
+        }
+
+        Ok(game_data)
+    }
+
+    /// Parses every data file from disk without validating them, failing with every file that couldn't be parsed
+    pub fn read() -> Result<Self, AnglerError> {
+        let mut errors = Vec::new();
+
+        let config = Config::load().map_err(|e| errors.push(e.to_string())).ok();
+        let fish = FishData::load().map_err(|e| errors.push(e.to_string())).ok();
+        let rods = RodData::load().map_err(|e| errors.push(e.to_string())).ok();
+        let multipliers = MultiplierData::load().map_err(|e| errors.push(e.to_string())).ok();
+        let bait = BaitData::load().map_err(|e| errors.push(e.to_string())).ok();
+        let achievements = AchievementData::load().map_err(|e| errors.push(e.to_string())).ok();
+        let locations = LocationData::load().map_err(|e| errors.push(e.to_string())).ok();
+        let weather = WeatherData::load().map_err(|e| errors.push(e.to_string())).ok();
+        let events = EventData::load().map_err(|e| errors.push(e.to_string())).ok();
+
+        let (Some(config), Some(fish), Some(rods), Some(multipliers), Some(bait), Some(achievements), Some(locations),
+            Some(weather), Some(events)) = (config, fish, rods, multipliers, bait, achievements, locations, weather, events) else {
+            return Err(AnglerError::InvalidData(errors));
+        };
+
+        Ok(Self { config, fish, rods, multipliers, bait, achievements, locations, weather, events })
+    }
+
+    /// Every problem the data validator finds across the files
+    pub fn issues(&self) -> Vec<Issue> {
+        validate_config(&self.config).into_iter()
+            .chain(validate(&self.fish, &self.rods))
+            .chain(validate_bait(&self.bait, &self.fish))
+            .chain(validate_multipliers(&self.multipliers))
+            .chain(validate_achievements(&self.achievements, &self.events))
+            .chain(validate_weather(&self.weather, &self.fish))
+            .chain(validate_locations(&self.locations, &self.fish, &self.rods, &self.weather))
+            .chain(validate_events(&self.events, &self.bait, &self.rods, &self.locations))
+            .collect()
+    }
+
+    /// Runs the data validator, logging warnings and returning the errors
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+
+        for issue in self.issues() {
+            match issue.severity {
+                Severity::Error => errors.push(issue.to_string()),
+                Severity::Warning => hey!("{}", issue),
+            }
+        }
+
+        errors
+    }
+}
+
+pub struct GameDataContainer;
+
+impl TypeMapKey for GameDataContainer {
+    type Value = Arc<RwLock<Arc<GameData>>>;
+}
+
+/// A snapshot of the live game data
+pub async fn game_data(ctx: &Context) -> Arc<GameData> {
+    let data = ctx.data.read().await;
+    let registry = data.get::<GameDataContainer>().expect("Game data was not registered");
+    let snapshot = registry.read().unwrap().clone();
+    snapshot
+}
+
+/// Loads the data files again and swaps them in atomically.
+/// If anything fails to load or validate, the live data is left untouched.
+pub async fn reload(ctx: &Context) -> Result<(), AnglerError> {
+    let game_data = GameData::load()?;
+
+    let data = ctx.data.read().await;
+    let registry = data.get::<GameDataContainer>().expect("Game data was not registered");
+    *registry.write().unwrap() = Arc::new(game_data);
+
+    Ok(())
+}
@@ -0,0 +1,69 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// The RNG every game roll goes through.
+/// Rolls are always made from a recorded seed so a bug report can be replayed exactly.
+pub type GameRng = StdRng;
+
+/// A fresh seed for a cast, purchase or restock. Log it alongside whatever it rolls.
+pub fn new_seed() -> u64 {
+    rand::thread_rng().gen()
+}
+
+fn seeded(seed: u64, stream: u8) -> GameRng {
+    let mut bytes = [0u8; 32];
+    bytes[..8].copy_from_slice(&seed.to_le_bytes());
+    bytes[8] = stream;
+    GameRng::from_seed(bytes)
+}
+
+/// Rolls made when a line is cast: the fish, whether it will be caught and how long it takes
+pub fn cast_rng(seed: u64) -> GameRng {
+    seeded(seed, 0)
+}
+
+/// Rolls made when a cast resolves. Kept on its own stream so a cast can be resolved
+/// long after it was rolled and still give the same result.
+pub fn catch_rng(seed: u64) -> GameRng {
+    seeded(seed, 1)
+}
+
+/// The roll made when the player reels in a bite, on its own stream so it doesn't shift the catch rolls
+pub fn reel_rng(seed: u64) -> GameRng {
+    seeded(seed, 3)
+}
+
+/// Rolls made for everything else: shop restocks and rod modifiers
+pub fn misc_rng(seed: u64) -> GameRng {
+    seeded(seed, 2)
+}
+
+/// The weather roll for one of a guild's weather periods, so everyone in the guild sees the same sky
+/// and the forecast knows what's coming
+pub fn weather_rng(guild_id: u64, period: i64) -> GameRng {
+    let mut bytes = [0u8; 32];
+    bytes[..8].copy_from_slice(&guild_id.to_le_bytes());
+    bytes[8] = 4;
+    bytes[16..24].copy_from_slice(&period.to_le_bytes());
+    GameRng::from_seed(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+    use super::{cast_rng, catch_rng, weather_rng};
+
+    #[test]
+    fn same_seed_gives_the_same_rolls() {
+        let first = cast_rng(1234).gen::<[u64; 4]>();
+        let second = cast_rng(1234).gen::<[u64; 4]>();
+        assert_eq!(first, second);
+        assert_eq!(weather_rng(1, 10).gen::<u64>(), weather_rng(1, 10).gen::<u64>());
+    }
+
+    #[test]
+    fn streams_are_independent() {
+        assert_ne!(cast_rng(1234).gen::<u64>(), catch_rng(1234).gen::<u64>());
+        assert_ne!(weather_rng(1, 10).gen::<u64>(), weather_rng(1, 11).gen::<u64>());
+    }
+}
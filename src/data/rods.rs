@@ -1,9 +1,9 @@
 use std::fmt::{Display, Formatter};
-use std::path::Path;
 use rand::Rng;
 use serde::{Deserialize, Serialize};
 use crate::data::multipliers::MultiplierData;
 use crate::data::shop::RodRarity;
+use crate::error::AnglerError;
 
 // https://docs.google.com/spreadsheets/d/1k_U3l-JPknjTrtXBf2-Y2J1kPrGCJLQs5KjHkO0jQ_E/edit?usp=sharing
 
@@ -17,12 +17,31 @@ pub struct BaseRod {
     pub weight_limit: u32,
     pub cost: f32,
     pub rarity: String,
+    /// The angler level needed to buy this rod
+    #[serde(default)]
+    pub required_level: u32,
 }
 
 impl BaseRod {
     pub fn get_rarity(&self) -> RodRarity {
         RodRarity::from_string(self.rarity.clone())
     }
+
+    /// The shop price after any shop discount
+    pub fn get_cost(&self, multipliers: &MultiplierData) -> u32 {
+        let mut cost = self.cost;
+
+        if multipliers.shop_discount != 0.0 {
+            cost -= cost * multipliers.shop_discount;
+        }
+
+        cost.round() as u32
+    }
+
+    /// What the shop pays for this rod secondhand
+    pub fn get_resale_value(&self, multipliers: &MultiplierData) -> u32 {
+        (self.get_cost(multipliers) as f32 * multipliers.resale).round() as u32
+    }
 }
 
 // todo this should change price as well?
@@ -48,17 +67,8 @@ pub struct RodData {
 }
 
 impl RodData {
-    pub fn load() -> Self {
-        let raw_path = "./data/rods.json".to_string();
-        let path = Path::new(raw_path.as_str());
-
-        if !path.exists() {
-            panic!("Failed to load rod data: file does not exist");
-        }
-
-        let contents = std::fs::read_to_string(path).unwrap();
-
-        serde_json::from_str(contents.as_str()).unwrap()
+    pub fn load() -> Result<Self, AnglerError> {
+        AnglerError::load_json("./data/rods.json")
     }
 
     pub fn get_base_by_name<S: Into<String>>(&self, name: S) -> Option<BaseRod> {
@@ -71,8 +81,7 @@ impl RodData {
         self.modifiers.clone().into_iter().find(|modifier| modifier.name == name)
     }
 
-    pub fn generate_modifier(&self) -> Option<RodModifier> {
-        let mut rng = rand::thread_rng();
+    pub fn generate_modifier<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<RodModifier> {
         let chance = rng.gen_range(0..100);
 
         if chance < 10 {
@@ -83,9 +92,7 @@ impl RodData {
         }
     }
 
-    pub fn generate_rod_base(&self, rarity: RodRarity) -> BaseRod {
-        let mut rng = rand::thread_rng();
-
+    pub fn generate_rod_base<R: Rng + ?Sized>(&self, rarity: RodRarity, rng: &mut R) -> BaseRod {
         let mut rods: Vec<&BaseRod> = self.rods.iter().filter(|r| r.get_rarity().get_ident() == rarity.get_ident()).collect();
         if rods.is_empty() {
             rods.push(self.rods.get(rng.gen_range(0..self.rods.len())).unwrap());
@@ -94,9 +101,9 @@ impl RodData {
         rods[rng.gen_range(0..rods.len())].clone()
     }
 
-    pub fn generate_rod(&self, rarity: RodRarity) -> Rod {
-        let base = self.generate_rod_base(rarity);
-        let modifier = self.generate_modifier();
+    pub fn generate_rod<R: Rng + ?Sized>(&self, rarity: RodRarity, rng: &mut R) -> Rod {
+        let base = self.generate_rod_base(rarity, rng);
+        let modifier = self.generate_modifier(rng);
 
         Rod {
             base,
@@ -148,14 +155,16 @@ impl Rod {
         weight_limit as u32
     }
 
-    pub fn random_catch_time(&self) -> f32 {
+    /// Rolls whether the fish on the line will be landed or get away
+    pub fn roll_will_catch<R: Rng + ?Sized>(&self, multipliers: &MultiplierData, rng: &mut R) -> bool {
+        rng.gen_range(0..1000) <= (self.get_catch_chance() + multipliers.catch_chance)
+    }
+
+    pub fn random_catch_time<R: Rng + ?Sized>(&self, multipliers: &MultiplierData, rng: &mut R) -> f32 {
         let catch_rate = self.get_catch_rate() as f32;
-        let mut rng = rand::thread_rng();
 
         let random_multiplier = rng.gen_range(0.8..1.2);
 
-        let multipliers = MultiplierData::load();
-
         let time = catch_rate * random_multiplier;
 
         time / multipliers.cast_time_divisor
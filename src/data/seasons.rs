@@ -0,0 +1,143 @@
+use std::fmt::{Display, Formatter};
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+/// A day of the year as (month, day)
+type MonthDay = (u32, u32);
+
+const MONTHS: [&str; 12] = ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"];
+
+/// Northern hemisphere seasons, by month
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum Season {
+    Spring,
+    Summer,
+    Autumn,
+    Winter,
+}
+
+impl Season {
+    /// The first and last day of the season
+    fn days(&self) -> (MonthDay, MonthDay) {
+        match self {
+            Season::Spring => ((3, 1), (5, 31)),
+            Season::Summer => ((6, 1), (8, 31)),
+            Season::Autumn => ((9, 1), (11, 30)),
+            Season::Winter => ((12, 1), (2, 29)),
+        }
+    }
+}
+
+impl Display for Season {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Season::Spring => write!(f, "Spring"),
+            Season::Summer => write!(f, "Summer"),
+            Season::Autumn => write!(f, "Autumn"),
+            Season::Winter => write!(f, "Winter"),
+        }
+    }
+}
+
+/// When a seasonal species can be caught
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ActiveWindow {
+    /// Once, from one YYYY-MM-DD date to another, inclusive
+    Dates { from: String, to: String },
+    /// Every year from one MM-DD day to another, inclusive. Runs over new year if `to` comes before `from`.
+    Yearly { from: String, to: String },
+    /// Every year during a season
+    Season { season: Season },
+}
+
+fn parse_date(date: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()
+}
+
+fn parse_month_day(day: &str) -> Option<MonthDay> {
+    let (month, day) = day.split_once('-')?;
+    let (month, day) = (month.parse().ok()?, day.parse().ok()?);
+    // checked against a leap year so 02-29 is allowed
+    NaiveDate::from_ymd_opt(2000, month, day).map(|_| (month, day))
+}
+
+/// The first date on or after `date` that falls on `day`, skipping years without a Feb 29
+fn next_month_day(date: NaiveDate, day: MonthDay) -> Option<NaiveDate> {
+    (date.year()..date.year() + 8)
+        .filter_map(|year| NaiveDate::from_ymd_opt(year, day.0, day.1))
+        .find(|start| *start >= date)
+}
+
+fn format_month_day(day: MonthDay) -> String {
+    format!("{} {}", MONTHS[(day.0 - 1) as usize], day.1)
+}
+
+impl ActiveWindow {
+    /// The recurring first and last day, for windows that come back every year
+    fn yearly_days(&self) -> Option<(MonthDay, MonthDay)> {
+        match self {
+            ActiveWindow::Dates { .. } => None,
+            ActiveWindow::Yearly { from, to } => Some((parse_month_day(from)?, parse_month_day(to)?)),
+            ActiveWindow::Season { season } => Some(season.days()),
+        }
+    }
+
+    /// Whether the dates in the window can be read, with the reason if not
+    pub fn check(&self) -> Result<(), String> {
+        match self {
+            ActiveWindow::Dates { from, to } => match (parse_date(from), parse_date(to)) {
+                (Some(start), Some(end)) if start <= end => Ok(()),
+                (Some(_), Some(_)) => Err(format!("the window {} to {} ends before it starts", from, to)),
+                _ => Err(format!("the window {} to {} isn't a pair of YYYY-MM-DD dates", from, to)),
+            },
+            ActiveWindow::Yearly { from, to } => match self.yearly_days() {
+                Some(_) => Ok(()),
+                None => Err(format!("the yearly window {} to {} isn't a pair of MM-DD days", from, to)),
+            },
+            ActiveWindow::Season { .. } => Ok(()),
+        }
+    }
+
+    pub fn is_active(&self, date: NaiveDate) -> bool {
+        if let ActiveWindow::Dates { from, to } = self {
+            return match (parse_date(from), parse_date(to)) {
+                (Some(start), Some(end)) => start <= date && date <= end,
+                _ => false,
+            };
+        }
+
+        let Some((start, end)) = self.yearly_days() else {
+            return false;
+        };
+        let today = (date.month(), date.day());
+        if start <= end {
+            start <= today && today <= end
+        } else {
+            today >= start || today <= end
+        }
+    }
+
+    /// The next day the window opens after `date`, or None if it never will again
+    pub fn next_start(&self, date: NaiveDate) -> Option<NaiveDate> {
+        if let ActiveWindow::Dates { from, .. } = self {
+            return parse_date(from).filter(|start| *start > date);
+        }
+
+        let (start, _) = self.yearly_days()?;
+        next_month_day(date.succ_opt()?, start)
+    }
+}
+
+impl Display for ActiveWindow {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ActiveWindow::Dates { from, to } => write!(f, "{} to {}", from, to),
+            ActiveWindow::Season { season } => write!(f, "{}", season),
+            ActiveWindow::Yearly { from, to } => match self.yearly_days() {
+                Some((start, end)) => write!(f, "{} to {}", format_month_day(start), format_month_day(end)),
+                None => write!(f, "{} to {}", from, to),
+            },
+        }
+    }
+}
@@ -1,10 +1,19 @@
 use std::fmt::Display;
+use std::str::FromStr;
 use chrono::{Duration, Local, NaiveDateTime};
 use rand::Rng;
 use serde::{Deserialize, Serialize};
-use serenity::all::UserId;
-use crate::data::rods::{Rod, RodData};
-use crate::say;
+use crate::data::bait::BaitData;
+use crate::data::rng::{misc_rng, new_seed};
+use crate::data::rods::RodData;
+use crate::error::AnglerError;
+use crate::{hey, say};
+
+const SHOP_PATH: &str = "./data/shop.json";
+
+/// Rods take shop slots 1 to ROD_STOCK, bait the slots after
+pub const ROD_STOCK: usize = 6;
+pub const BAIT_STOCK: usize = 3;
 
 fn next_midnight() -> NaiveDateTime {
     let now = Local::now();
@@ -57,8 +66,7 @@ impl RodRarity {
         }
     }
 
-    pub fn random() -> Self {
-        let mut rng = rand::thread_rng();
+    pub fn random<R: Rng + ?Sized>(rng: &mut R) -> Self {
         let num = rng.gen_range(0..10000);
 
         let mut combined = 0;
@@ -85,6 +93,21 @@ impl RodRarity {
     }
 }
 
+impl FromStr for RodRarity {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Common" => Ok(RodRarity::Common),
+            "Uncommon" => Ok(RodRarity::Uncommon),
+            "Rare" => Ok(RodRarity::Rare),
+            "Epic" => Ok(RodRarity::Epic),
+            "Unobtainable" => Ok(RodRarity::Unobtainable),
+            _ => Err(()),
+        }
+    }
+}
+
 impl Display for RodRarity {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -97,63 +120,55 @@ impl Display for RodRarity {
     }
 }
 
-pub enum BuyError {
-    NoMoney,
-    InvalidRod,
-}
-
-impl Display for BuyError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            BuyError::NoMoney => write!(f, "You don't have enough money to buy this rod!"),
-            BuyError::InvalidRod => write!(f, "That rod is no longer available!"),
-        }
-    }
-}
-
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Shop {
     pub refresh: i64,
+    /// The seed this stock was rolled from
+    #[serde(default)]
+    pub seed: u64,
     pub rods: Vec<String>,
-    // todo: bait goes here
+    #[serde(default)]
+    pub bait: Vec<String>,
 }
 
 impl Shop {
 
-    pub fn create() -> Shop {
-        let rod_data = RodData::load();
+    pub fn create(rod_data: &RodData, bait_data: &BaitData, seed: u64) -> Result<Shop, AnglerError> {
+        let mut rng = misc_rng(seed);
 
         // create a shop
         let mut shop = Self {
             refresh: next_midnight().timestamp(),
+            seed,
             rods: Vec::new(),
+            bait: Vec::new(),
         };
 
         // generate daily rods rods and add them to the shop
-        shop.rods.push(rod_data.generate_rod_base(RodRarity::Common).name);
-        shop.rods.push(rod_data.generate_rod_base(RodRarity::Common).name);
+        shop.rods.push(rod_data.generate_rod_base(RodRarity::Common, &mut rng).name);
+        shop.rods.push(rod_data.generate_rod_base(RodRarity::Common, &mut rng).name);
 
-        shop.rods.push(rod_data.generate_rod_base(RodRarity::Uncommon).name);
-        shop.rods.push(rod_data.generate_rod_base(RodRarity::Uncommon).name);
+        shop.rods.push(rod_data.generate_rod_base(RodRarity::Uncommon, &mut rng).name);
+        shop.rods.push(rod_data.generate_rod_base(RodRarity::Uncommon, &mut rng).name);
 
-        shop.rods.push(rod_data.generate_rod_base(RodRarity::Rare).name);
+        shop.rods.push(rod_data.generate_rod_base(RodRarity::Rare, &mut rng).name);
 
-        shop.rods.push(rod_data.generate_rod_base(RodRarity::Epic).name);
+        shop.rods.push(rod_data.generate_rod_base(RodRarity::Epic, &mut rng).name);
 
-        // write the shop to a file
-        let serialized = serde_json::to_string(&shop).unwrap();
+        // bait is rolled after the rods so older seeds still give the same rods
+        shop.bait = bait_data.generate_stock(BAIT_STOCK, &mut rng);
 
-        let raw_path = "./data/shop.json".to_string();
+        shop.save()?;
 
-        let path = std::path::Path::new(raw_path.as_str());
-
-        if !path.exists() {
-            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
-        }
+        Ok(shop)
+    }
 
-        std::fs::write(path, serialized).unwrap();
+    fn save(&self) -> Result<(), AnglerError> {
+        let serialized = serde_json::to_string(self)
+            .map_err(|source| AnglerError::Parse { path: SHOP_PATH.to_string(), source })?;
 
-        shop
+        std::fs::write(SHOP_PATH, serialized)
+            .map_err(|source| AnglerError::Io { path: SHOP_PATH.to_string(), source })
     }
 
     pub fn get_time_until_restock(&self) -> String {
@@ -181,86 +196,36 @@ impl Shop {
         false
     }
 
-    pub fn load() -> Self {
-        let raw_path = "./data/shop.json".to_string();
-        let path = std::path::Path::new(raw_path.as_str());
-
-        if !path.exists() {
-            return Self::create();
+    pub fn load(rod_data: &RodData, bait_data: &BaitData) -> Result<Self, AnglerError> {
+        if !std::path::Path::new(SHOP_PATH).exists() {
+            return Self::create(rod_data, bait_data, new_seed());
         }
 
-        let contents = std::fs::read_to_string(path).unwrap();
+        let mut shop: Shop = match AnglerError::load_json(SHOP_PATH) {
+            Ok(shop) => shop,
+            // the shop is only today's stock, so a broken file is just restocked
+            Err(e @ AnglerError::Parse { .. }) => {
+                hey!("{}, restocking the shop", e);
+                return Self::create(rod_data, bait_data, new_seed());
+            }
+            Err(e) => return Err(e),
+        };
 
-        let mut shop: Shop = serde_json::from_str(contents.as_str()).unwrap();
+        // shops stocked before bait existed get theirs straight away instead of at the next restock
+        if shop.bait.is_empty() && !bait_data.bait.is_empty() {
+            shop.bait = bait_data.generate_stock(BAIT_STOCK, &mut misc_rng(shop.seed));
+            shop.save()?;
+        }
 
         // check if refresh is needed
         if shop.should_refresh() {
-            say!("Refreshing shop");
-            let mut new_shop = Self::create();
+            let seed = new_seed();
+            say!("Refreshing shop (seed: {})", seed);
+            let mut new_shop = Self::create(rod_data, bait_data, seed)?;
             new_shop.refresh = next_midnight().timestamp();
             shop = new_shop;
         }
 
-        shop
-    }
-
-    pub fn sell_rod(&mut self, spot: usize, guild_id: u64, user_id: &UserId) -> Result<String, BuyError> {
-        if spot >= self.rods.len() {
-            return Err(BuyError::InvalidRod);
-        }
-
-        let rod_data = RodData::load();
-
-        let base_rod_name = self.rods.get(spot).unwrap().clone();
-        let base_rod = rod_data.get_base_by_name(base_rod_name).unwrap();
-
-        let mut user_file = crate::data::userfile::read_userfile(user_id, guild_id);
-
-        let multiplier = crate::data::multipliers::MultiplierData::load();
-
-        let mut cost = base_rod.cost;
-
-        if multiplier.shop_discount != 0.0 {
-            cost -= cost * multiplier.shop_discount;
-        }
-
-        let cost = cost.round() as u32;
-
-        // ensure the user has enough money to buy the rod
-        if user_file.money < cost {
-            return Err(BuyError::NoMoney);
-        }
-
-        // remove the rod from the shop
-        //let users_rod = self.rods.remove(spot);
-
-        // remove the money from the user
-        user_file.money -= cost;
-
-        let modifier = rod_data.generate_modifier();
-
-        // generate the rod
-        let rod = Rod {
-            base: base_rod,
-            modifier: modifier.clone(),
-        };
-
-        // add the rod to the user's inventory
-        user_file.rod_name = rod.base.name.clone();
-        if let Some(m) = &rod.modifier {
-            user_file.rod_modifier = Some(m.name.clone());
-        } else {
-            user_file.rod_modifier = None;
-        }
-
-        // update the user's file
-        crate::data::userfile::update_userfile(user_id, user_file, guild_id);
-
-        if let Some(m) = &modifier {
-            Ok(format!("You now own a {}!\n  Your rod has a modifier: {}!", rod, m.name))
-        } else {
-            Ok(format!("You now own a {}!", rod))
-        }
+        Ok(shop)
     }
-
-}
\ No newline at end of file
+}
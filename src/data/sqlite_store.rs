@@ -0,0 +1,222 @@
+use std::sync::Mutex;
+use rusqlite::{Connection, OptionalExtension, params};
+use serenity::all::UserId;
+use chrono::Local;
+use crate::data::guildfile::GuildValues;
+use crate::data::leaderboard::{rank, Category};
+use crate::data::migrations::{parse_user_values, ProfileError, CURRENT_VERSION};
+use crate::data::userfile::{UserStore, UserValues};
+use crate::error::AnglerError;
+use crate::{hey, nay, yay};
+
+/// Stores every user profile in a single embedded SQLite database.
+/// Profiles are kept as JSON so cross-user queries can use `json_extract`.
+pub struct SqliteStore {
+    conn: Mutex<Connection>,
+}
+
+impl SqliteStore {
+    /// Opens the database at `path`, creating it and its tables if they don't exist yet
+    pub fn open(path: &str) -> Result<Self, AnglerError> {
+        let database_error = |source| AnglerError::Database { path: path.to_string(), source };
+        let conn = Connection::open(path).map_err(database_error)?;
+
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS users (
+                guild_id INTEGER NOT NULL,
+                user_id INTEGER NOT NULL,
+                data TEXT NOT NULL,
+                PRIMARY KEY (guild_id, user_id)
+            );
+            CREATE TABLE IF NOT EXISTS guilds (
+                guild_id INTEGER PRIMARY KEY,
+                data TEXT NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS quarantine (
+                guild_id INTEGER NOT NULL,
+                user_id INTEGER NOT NULL,
+                data TEXT NOT NULL,
+                quarantined_at INTEGER NOT NULL
+            );"
+        ).map_err(database_error)?;
+
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    /// Copies every profile out of another store when this database has no users yet,
+    /// so switching backends doesn't wipe everyone's progress
+    pub fn import_if_empty(&self, from: &dyn UserStore) {
+        let count: i64 = self.conn.lock().unwrap()
+            .query_row("SELECT COUNT(*) FROM users", [], |row| row.get(0))
+            .unwrap_or(0);
+        if count > 0 {
+            return;
+        }
+
+        let mut imported = 0;
+        for guild_id in from.guilds() {
+            self.write_guild(guild_id, &from.read_guild(guild_id));
+            for (id, user_values) in from.users(guild_id) {
+                self.write(&id, &user_values, guild_id);
+                imported += 1;
+            }
+        }
+
+        if imported > 0 {
+            yay!("Imported {} user profiles into the SQLite store", imported);
+        }
+    }
+
+    /// Moves an unreadable profile into the quarantine table and replaces it with a fresh one
+    fn quarantine_user(&self, id: &UserId, guild_id: u64, contents: String, reason: String) -> UserValues {
+        hey!("Profile for {} in guild {} is unreadable ({}), quarantining it and starting fresh", id, guild_id, reason);
+
+        if let Err(e) = self.conn.lock().unwrap().execute(
+            "INSERT INTO quarantine (guild_id, user_id, data, quarantined_at) VALUES (?1, ?2, ?3, ?4)",
+            params![guild_id as i64, id.get() as i64, contents, Local::now().timestamp()]) {
+            nay!("Failed to quarantine profile for {} in guild {}: {}", id, guild_id, e);
+        }
+
+        let user_values = UserValues::default();
+        self.write(id, &user_values, guild_id);
+        user_values
+    }
+}
+
+impl UserStore for SqliteStore {
+    fn read(&self, id: &UserId, guild_id: u64) -> UserValues {
+        let existing: Option<String> = {
+            let conn = self.conn.lock().unwrap();
+            conn.query_row("SELECT data FROM users WHERE guild_id = ?1 AND user_id = ?2",
+                           params![guild_id as i64, id.get() as i64], |row| row.get(0))
+                .optional()
+                .unwrap()
+        };
+
+        match existing {
+            Some(contents) => match parse_user_values(contents.as_str()) {
+                Ok((user_values, migrated)) => {
+                    if migrated {
+                        self.write(id, &user_values, guild_id);
+                    }
+                    user_values
+                }
+                Err(ProfileError::TooNew(version)) => {
+                    nay!("Profile for {} in guild {} is from a newer build (version {}), leaving it alone until that build is back",
+                        id, guild_id, version);
+                    UserValues::default()
+                }
+                Err(ProfileError::Unreadable(reason)) => self.quarantine_user(id, guild_id, contents, reason),
+            },
+            None => {
+                let user_values = UserValues::default();
+                self.write(id, &user_values, guild_id);
+                user_values
+            }
+        }
+    }
+
+    fn write(&self, id: &UserId, user_values: &UserValues, guild_id: u64) {
+        let serialized = serde_json::to_string(user_values).unwrap();
+
+        // profiles from a newer build are left as they are
+        let conn = self.conn.lock().unwrap();
+        match conn.execute("INSERT INTO users (guild_id, user_id, data) VALUES (?1, ?2, ?3)
+                            ON CONFLICT (guild_id, user_id) DO UPDATE SET data = excluded.data
+                            WHERE COALESCE(json_extract(users.data, '$.version'), 0) <= ?4",
+                           params![guild_id as i64, id.get() as i64, serialized, CURRENT_VERSION]) {
+            Ok(0) => nay!("Refusing to overwrite user {} in guild {}, their profile is from a newer build", id, guild_id),
+            Ok(_) => {}
+            Err(e) => nay!("Failed to write user {} in guild {}: {}", id, guild_id, e),
+        }
+    }
+
+    fn guilds(&self) -> Vec<u64> {
+        let conn = self.conn.lock().unwrap();
+        let mut statement = conn.prepare("SELECT guild_id FROM users UNION SELECT guild_id FROM guilds").unwrap();
+        let rows = statement.query_map([], |row| row.get::<_, i64>(0)).unwrap();
+
+        rows.filter_map(|row| row.ok()).map(|id| id as u64).collect()
+    }
+
+    fn users(&self, guild_id: u64) -> Vec<(UserId, UserValues)> {
+        let ids: Vec<i64> = {
+            let conn = self.conn.lock().unwrap();
+            let mut statement = conn.prepare("SELECT user_id FROM users WHERE guild_id = ?1").unwrap();
+            let rows = statement.query_map(params![guild_id as i64], |row| row.get(0)).unwrap();
+            rows.filter_map(|row| row.ok()).collect()
+        };
+
+        // go through `read` so every profile is migrated or quarantined the same way
+        ids.into_iter()
+            .map(|id| UserId::new(id as u64))
+            .map(|id| (id, self.read(&id, guild_id)))
+            .collect()
+    }
+
+    fn leaderboard(&self, guild_id: u64, category: Category) -> Vec<(UserId, f64)> {
+        let conn = self.conn.lock().unwrap();
+        let query = format!("SELECT user_id, {} FROM users WHERE guild_id = ?1", category.sql());
+
+        let mut statement = match conn.prepare(query.as_str()) {
+            Ok(statement) => statement,
+            Err(e) => {
+                nay!("Failed to query the {} leaderboard: {}", category.id(), e);
+                return Vec::new();
+            }
+        };
+        let Ok(rows) = statement.query_map(params![guild_id as i64], |row| {
+            Ok((UserId::new(row.get::<_, i64>(0)? as u64), row.get::<_, Option<f64>>(1)?.unwrap_or(0.0)))
+        }) else {
+            return Vec::new();
+        };
+
+        rank(rows.filter_map(|row| row.ok()).collect())
+    }
+
+    fn read_guild(&self, guild_id: u64) -> GuildValues {
+        let existing: Option<String> = {
+            let conn = self.conn.lock().unwrap();
+            conn.query_row("SELECT data FROM guilds WHERE guild_id = ?1", params![guild_id as i64], |row| row.get(0))
+                .optional()
+                .unwrap_or_else(|e| {
+                    nay!("Failed to read guild {}: {}", guild_id, e);
+                    None
+                })
+        };
+
+        let Some(contents) = existing else {
+            return GuildValues::default();
+        };
+
+        serde_json::from_str(contents.as_str()).unwrap_or_else(|e| {
+            hey!("Guild {} is unreadable ({}), starting fresh", guild_id, e);
+            GuildValues::default()
+        })
+    }
+
+    fn write_guild(&self, guild_id: u64, guild_values: &GuildValues) {
+        let serialized = serde_json::to_string(guild_values).unwrap();
+
+        let conn = self.conn.lock().unwrap();
+        if let Err(e) = conn.execute("INSERT INTO guilds (guild_id, data) VALUES (?1, ?2)
+                                      ON CONFLICT (guild_id) DO UPDATE SET data = excluded.data",
+                                     params![guild_id as i64, serialized]) {
+            nay!("Failed to write guild {}: {}", guild_id, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SqliteStore;
+    use crate::error::AnglerError;
+
+    #[test]
+    fn bad_paths_fail_to_open_instead_of_panicking() {
+        let result = SqliteStore::open("./data/no-such-directory/users.db");
+        assert!(matches!(result, Err(AnglerError::Database { .. })));
+    }
+}
@@ -0,0 +1,152 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use serde::{Deserialize, Serialize};
+use crate::data::fish::Fish;
+
+/// Share of the prize pool, in percent, paid to first, second and third place
+pub const PRIZE_SPLIT: [u32; 3] = [50, 30, 20];
+
+/// How tournament entries are ranked
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Scoring {
+    Heaviest,
+    TotalValue,
+    Species,
+}
+
+impl Scoring {
+    pub const ALL: [Scoring; 3] = [Scoring::Heaviest, Scoring::TotalValue, Scoring::Species];
+
+    /// Stable name used in command options
+    pub fn id(&self) -> &'static str {
+        match self {
+            Scoring::Heaviest => "heaviest",
+            Scoring::TotalValue => "value",
+            Scoring::Species => "species",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|scoring| scoring.id() == id)
+    }
+
+    pub fn format(&self, score: f64) -> String {
+        match self {
+            Scoring::Heaviest => format!("{:.1}lbs", score),
+            Scoring::TotalValue => format!("${}", score),
+            Scoring::Species => format!("{} species", score),
+        }
+    }
+}
+
+impl Display for Scoring {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Scoring::Heaviest => write!(f, "Heaviest fish"),
+            Scoring::TotalValue => write!(f, "Total value"),
+            Scoring::Species => write!(f, "Most species"),
+        }
+    }
+}
+
+/// A user's catches during a tournament
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Entry {
+    pub heaviest: f32,
+    pub value: u32,
+    pub species: Vec<String>,
+}
+
+impl Entry {
+    fn score(&self, scoring: Scoring) -> f64 {
+        match scoring {
+            Scoring::Heaviest => self.heaviest as f64,
+            Scoring::TotalValue => self.value as f64,
+            Scoring::Species => self.species.len() as f64,
+        }
+    }
+}
+
+/// A guild's running tournament, kept in `GuildValues` so a restart doesn't lose it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Tournament {
+    /// Where the results are posted
+    pub channel_id: u64,
+    /// Unix timestamps in seconds
+    pub started_at: i64,
+    pub ends_at: i64,
+    pub scoring: Scoring,
+    /// Only this species counts, if set
+    pub species: Option<String>,
+    pub prize_pool: u32,
+    /// user id -> their catches
+    pub entries: HashMap<u64, Entry>,
+}
+
+impl Tournament {
+    pub fn new(channel_id: u64, started_at: i64, minutes: i64, scoring: Scoring, species: Option<String>,
+               prize_pool: u32) -> Self {
+        Self {
+            channel_id,
+            started_at,
+            ends_at: started_at + minutes * 60,
+            scoring,
+            species,
+            prize_pool,
+            entries: HashMap::new(),
+        }
+    }
+
+    /// Counts a landed fish if it was caught during the tournament and matches the species filter.
+    /// Returns whether it counted.
+    pub fn record_catch(&mut self, user_id: u64, fish: &Fish, value: u32, caught_at: i64) -> bool {
+        if caught_at < self.started_at || caught_at >= self.ends_at {
+            return false;
+        }
+        if self.species.as_ref().is_some_and(|species| *species != fish.fish_type.name) {
+            return false;
+        }
+
+        let entry = self.entries.entry(user_id).or_default();
+        entry.heaviest = entry.heaviest.max(fish.weight);
+        entry.value += value;
+        if !entry.species.contains(&fish.fish_type.name) {
+            entry.species.push(fish.fish_type.name.clone());
+        }
+        true
+    }
+
+    /// Every entrant with a score, best first. Ties are broken by user id so the order is stable.
+    pub fn standings(&self) -> Vec<(u64, f64)> {
+        let mut standings = self.entries.iter()
+            .map(|(id, entry)| (*id, entry.score(self.scoring)))
+            .filter(|(_, score)| *score > 0.0)
+            .collect::<Vec<(u64, f64)>>();
+        standings.sort_by(|(a_id, a), (b_id, b)| b.total_cmp(a).then(a_id.cmp(b_id)));
+        standings
+    }
+
+    /// What each of the top places wins
+    pub fn payouts(&self) -> Vec<(u64, u32)> {
+        self.standings().into_iter().zip(PRIZE_SPLIT)
+            .map(|((id, _), percent)| (id, (self.prize_pool as u64 * percent as u64 / 100) as u32))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Entry, Scoring, Tournament};
+
+    #[test]
+    fn payouts_split_large_pools() {
+        let mut tournament = Tournament::new(0, 0, 60, Scoring::TotalValue, None, u32::MAX);
+        for (id, value) in [(1, 30), (2, 20), (3, 10)] {
+            tournament.entries.insert(id, Entry { value, ..Entry::default() });
+        }
+
+        let payouts = tournament.payouts();
+        assert_eq!(payouts, vec![(1, u32::MAX / 2), (2, (u32::MAX as u64 * 30 / 100) as u32),
+                                 (3, (u32::MAX as u64 * 20 / 100) as u32)]);
+    }
+}
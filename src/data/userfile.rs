@@ -1,127 +1,256 @@
-use std::path::Path;
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::sync::{Arc, Mutex, OnceLock};
 use serde::{Deserialize, Serialize};
 use serenity::all::UserId;
+use crate::data::bait::BaitStack;
+use crate::data::config::{Config, StorageBackend};
+use crate::data::contracts::ContractProgress;
+use crate::data::cooler::CooledFish;
+use crate::data::guildfile::GuildValues;
+use crate::data::json_store::JsonStore;
+use crate::data::leaderboard::Category;
+use crate::data::migrations::CURRENT_VERSION;
+use crate::data::records::Records;
 use crate::data::rods::{Rod, RodData};
-use crate::nay;
-
-const USERFILES_DIR: &str = "./data/guilds/";
+use crate::data::sqlite_store::SqliteStore;
+use crate::error::AnglerError;
+use crate::{nay, say};
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct UserValues {
+    pub version: u32,
     pub fish_caught: u32,
     pub money: u32,
-    pub rod_name: String,
-    pub rod_modifier: Option<String>,
-    // todo: bait
+    /// Total XP earned, the level is worked out from this
+    pub xp: u32,
+    /// Money earned from selling fish and rods, never goes down
+    pub total_earned: u32,
+    pub heaviest_catch: Option<HeaviestCatch>,
+    /// Personal bests for each species, the holder is always left as 0
+    pub records: Records,
+    /// Every rod the user owns, in the order they got them
+    pub rods: Vec<OwnedRod>,
+    /// Index into `rods` of the rod used for casting
+    pub equipped: usize,
+    /// Caught fish that haven't been sold yet
+    pub cooler: Vec<CooledFish>,
+    pub bait: Vec<BaitStack>,
+    /// Fish landed of each rarity
+    pub rarities_caught: HashMap<String, u32>,
+    pub line_breaks: u32,
+    /// How many times each random event has happened to the user, by id
+    pub events: HashMap<String, u32>,
+    /// Timestamp the user can cast again at, after an event held them up
+    pub tangled_until: i64,
+    /// Ids of the unlocked achievements
+    pub achievements: Vec<String>,
+    /// The most recent title earned from an achievement
+    pub title: Option<String>,
+    /// Progress on the guild's daily contracts
+    pub contracts: ContractProgress,
+    /// Name of the location the user fishes at, the first in locations.json if unset
+    pub location: Option<String>,
+    /// Timestamp of the user's last trip
+    pub traveled_at: i64,
     pub cast: bool,
     pub has_seen: Vec<String>
 }
 
-impl UserValues {
-    pub fn get_rod(&self) -> Rod {
-        let rod_data = RodData::load();
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct HeaviestCatch {
+    pub species: String,
+    pub weight: f32,
+}
+
+/// A rod in a user's inventory, stored by name so rods.json can be rebalanced
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct OwnedRod {
+    pub name: String,
+    pub modifier: Option<String>,
+}
+
+impl OwnedRod {
+    pub fn new(rod: &Rod) -> Self {
+        Self {
+            name: rod.base.name.clone(),
+            modifier: rod.modifier.as_ref().map(|m| m.name.clone()),
+        }
+    }
 
-        let rod_base = rod_data.get_base_by_name(self.rod_name.as_str()).unwrap();
+    /// Looks the rod up in rods.json, failing if its base or modifier no longer exists
+    pub fn to_rod(&self, rod_data: &RodData) -> Result<Rod, AnglerError> {
+        let rod_base = rod_data.get_base_by_name(self.name.as_str())
+            .ok_or_else(|| AnglerError::UnknownRod(self.name.clone()))?;
 
-        let modifier = self.rod_modifier.as_ref().map(|modifier|
-            rod_data.get_modifier_by_name(modifier.as_str()).unwrap());
+        let modifier = match &self.modifier {
+            Some(modifier) => Some(rod_data.get_modifier_by_name(modifier.as_str())
+                .ok_or_else(|| AnglerError::UnknownModifier(modifier.clone()))?),
+            None => None,
+        };
 
-        Rod {
+        Ok(Rod {
             base: rod_base,
             modifier,
+        })
+    }
+}
+
+impl Display for OwnedRod {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.modifier {
+            Some(modifier) => write!(f, "{} {}", modifier, self.name),
+            None => write!(f, "{}", self.name),
         }
     }
 }
 
+/// The rod every new angler starts out with
+pub const DEFAULT_ROD: &str = "Stick with String";
+
+impl UserValues {
+    /// The user's equipped rod
+    pub fn get_rod(&self, rod_data: &RodData) -> Result<Rod, AnglerError> {
+        self.rods.get(self.equipped)
+            .ok_or_else(|| AnglerError::UnknownRod(format!("inventory slot {}", self.equipped + 1)))?
+            .to_rod(rod_data)
+    }
+
+    /// Pays the user, counting it towards their lifetime earnings
+    pub fn earn(&mut self, amount: u32) {
+        self.money = self.money.saturating_add(amount);
+        self.total_earned = self.total_earned.saturating_add(amount);
+    }
+}
+
 impl Default for UserValues {
     fn default() -> Self {
         Self {
+            version: CURRENT_VERSION,
             fish_caught: 0,
             money: 0,
-            rod_name: "Stick with String".to_string(),
-            rod_modifier: None,
+            xp: 0,
+            total_earned: 0,
+            heaviest_catch: None,
+            records: Records::new(),
+            rods: vec![OwnedRod { name: DEFAULT_ROD.to_string(), modifier: None }],
+            equipped: 0,
+            cooler: vec![],
+            bait: vec![],
+            rarities_caught: HashMap::new(),
+            line_breaks: 0,
+            events: HashMap::new(),
+            tangled_until: 0,
+            achievements: vec![],
+            title: None,
+            contracts: ContractProgress::default(),
+            location: None,
+            traveled_at: 0,
             cast: false,
             has_seen: vec![]
         }
     }
 }
 
-pub fn get_userfile_path(id: &UserId, guild_id: u64) -> String {
-    format!("{}{}/users/{}.json", USERFILES_DIR, guild_id, id)
-}
+/// Storage for per-guild user profiles and each guild's shared state.
+/// Commands should only ever go through this trait so the backend can be swapped in `Config`.
+pub trait UserStore: Send + Sync {
+    /// Reads a user's profile, creating a fresh one if the user has never played in this guild.
+    /// Older profiles are migrated to the current schema and unreadable ones are quarantined and replaced.
+    /// Profiles from a newer build are left in place and read as a blank profile that `write` won't save.
+    fn read(&self, id: &UserId, guild_id: u64) -> UserValues;
 
-pub fn set_userfile_casting_false(path: String) {
-    let raw_path = path.to_string();
-    let path = Path::new(raw_path.as_str());
+    /// Overwrites a user's profile, unless the stored one is from a newer build.
+    /// Prefer `modify_user`, which holds the user's lock across the read and the write.
+    fn write(&self, id: &UserId, user_values: &UserValues, guild_id: u64);
 
-    if !path.exists() {
-        return;
-    }
+    /// Every guild that has at least one user profile
+    fn guilds(&self) -> Vec<u64>;
 
-    let contents = std::fs::read_to_string(path).unwrap();
+    /// Every user profile stored for a guild
+    fn users(&self, guild_id: u64) -> Vec<(UserId, UserValues)>;
 
-    let mut user_values: UserValues = serde_json::from_str(contents.as_str()).unwrap();
-    user_values.cast = false;
+    /// Every player in a guild with a score in `category`, highest first
+    fn leaderboard(&self, guild_id: u64, category: Category) -> Vec<(UserId, f64)>;
 
-    // write the file
-    let serialized = serde_json::to_string(&user_values).unwrap();
+    /// Reads a guild's shared state, or the defaults if nothing has been saved yet
+    fn read_guild(&self, guild_id: u64) -> GuildValues;
 
-    std::fs::write(path, serialized).unwrap();
+    /// Overwrites a guild's shared state. Prefer `modify_guild`.
+    fn write_guild(&self, guild_id: u64, guild_values: &GuildValues);
 }
 
-pub fn userfile_exists(id: &UserId, guild_id: u64) -> bool {
-    let raw_path = get_userfile_path(id, guild_id);
-    let path = Path::new(raw_path.as_str());
-
-    path.exists()
-}
+static STORE: OnceLock<Box<dyn UserStore>> = OnceLock::new();
 
-pub fn create_userfile(id: &UserId, guild_id: u64) {
-    let raw_path = get_userfile_path(id, guild_id);
-    let path = Path::new(raw_path.as_str());
-
-    if !path.exists() {
-        // make the directories
-        if let Err(e) = std::fs::create_dir_all(path.parent().unwrap()) {
-            nay!("Failed to create userfile directories: {}", e);
-            return;
-        }
-        // create the file
-        if let Err(e) = std::fs::File::create(path) {
-            nay!("Failed to create userfile: {}", e);
-            return;
+/// The user store selected by `storage.backend` in the config, opened on first use
+pub fn store() -> &'static dyn UserStore {
+    STORE.get_or_init(|| {
+        let storage = Config::load().map(|config| config.storage).unwrap_or_default();
+        match storage.backend {
+            StorageBackend::Json => {
+                say!("Using the JSON user store");
+                Box::new(JsonStore::new())
+            }
+            StorageBackend::Sqlite => {
+                say!("Using the SQLite user store at {}", storage.sqlite_path);
+                match SqliteStore::open(storage.sqlite_path.as_str()) {
+                    Ok(store) => {
+                        store.import_if_empty(&JsonStore::new());
+                        Box::new(store)
+                    }
+                    Err(e) => {
+                        nay!("{}, falling back to the JSON user store", e);
+                        Box::new(JsonStore::new())
+                    }
+                }
+            }
         }
-    }
+    }).as_ref()
+}
 
-    let user_values = UserValues::default();
+/// (guild id, user id) -> that user's lock
+type UserLocks = Mutex<HashMap<(u64, u64), Arc<Mutex<()>>>>;
 
-    let serialized = serde_json::to_string(&user_values).unwrap();
+static USER_LOCKS: OnceLock<UserLocks> = OnceLock::new();
 
-    std::fs::write(path, serialized).unwrap();
+fn user_lock(guild_id: u64, id: &UserId) -> Arc<Mutex<()>> {
+    let mut locks = USER_LOCKS.get_or_init(|| Mutex::new(HashMap::new())).lock().unwrap();
+    locks.entry((guild_id, id.get())).or_default().clone()
 }
 
-pub fn read_userfile(id: &UserId, guild_id: u64) -> UserValues {
-    let raw_path = get_userfile_path(id, guild_id);
-    let path = Path::new(raw_path.as_str());
+/// Reads a user's profile, lets `modify` change it, and writes it back while holding that user's lock,
+/// so concurrent commands and scheduled catches can never overwrite each other's changes.
+/// Whatever `modify` returns is handed back to the caller.
+pub fn modify_user<R>(guild_id: u64, id: &UserId, modify: impl FnOnce(&mut UserValues) -> R) -> R {
+    let lock = user_lock(guild_id, id);
+    let _guard = lock.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
 
-    if !path.exists() {
-        create_userfile(id, guild_id);
-    }
-
-    let contents = std::fs::read_to_string(path).unwrap();
+    let store = store();
+    let mut user_values = store.read(id, guild_id);
+    let result = modify(&mut user_values);
+    store.write(id, &user_values, guild_id);
 
-    serde_json::from_str(contents.as_str()).unwrap()
+    result
 }
 
-pub fn update_userfile(id: &UserId, user_values: UserValues, guild_id: u64) {
-    let raw_path = get_userfile_path(id, guild_id);
-    let path = Path::new(raw_path.as_str());
+/// Like `modify_user`, but for two different users at once so a transfer between them is all or nothing.
+/// The locks are always taken in id order, so two transfers between the same pair can't deadlock.
+pub fn modify_users<R>(guild_id: u64, a: &UserId, b: &UserId,
+                       modify: impl FnOnce(&mut UserValues, &mut UserValues) -> R) -> R {
+    debug_assert_ne!(a, b, "modify_users needs two different users");
 
-    if !path.exists() {
-        create_userfile(id, guild_id);
-    }
+    let (first, second) = if a < b { (a, b) } else { (b, a) };
+    let first_lock = user_lock(guild_id, first);
+    let second_lock = user_lock(guild_id, second);
+    let _first_guard = first_lock.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    let _second_guard = second_lock.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
 
-    let serialized = serde_json::to_string(&user_values).unwrap();
+    let store = store();
+    let mut a_values = store.read(a, guild_id);
+    let mut b_values = store.read(b, guild_id);
+    let result = modify(&mut a_values, &mut b_values);
+    store.write(a, &a_values, guild_id);
+    store.write(b, &b_values, guild_id);
 
-    std::fs::write(path, serialized).unwrap();
+    result
 }
@@ -0,0 +1,546 @@
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+use std::str::FromStr;
+use crate::data::achievements::{AchievementData, Trigger};
+use crate::data::bait::BaitData;
+use crate::data::config::Config;
+use crate::data::events::{Effect, EventData};
+use crate::data::fish::{FishData, FishRarity};
+use crate::data::locations::LocationData;
+use crate::data::multipliers::MultiplierData;
+use crate::data::rods::{BaseRod, RodData};
+use crate::data::shop::RodRarity;
+use crate::data::userfile::DEFAULT_ROD;
+use crate::data::weather::{Effects, WeatherData};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Severity {
+    /// The bot would panic or silently misbehave with this data
+    Error,
+    /// Suspicious, but can't happen through normal play
+    Warning,
+}
+
+#[derive(Debug, Clone)]
+pub struct Issue {
+    pub severity: Severity,
+    pub file: &'static str,
+    pub message: String,
+}
+
+impl Display for Issue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.file, self.message)
+    }
+}
+
+struct Issues(Vec<Issue>);
+
+impl Issues {
+    fn error<S: Into<String>>(&mut self, file: &'static str, message: S) {
+        self.0.push(Issue { severity: Severity::Error, file, message: message.into() });
+    }
+
+    fn warn<S: Into<String>>(&mut self, file: &'static str, message: S) {
+        self.0.push(Issue { severity: Severity::Warning, file, message: message.into() });
+    }
+}
+
+fn find_duplicates<'a>(names: impl Iterator<Item = &'a String>) -> Vec<&'a String> {
+    let mut seen = HashSet::new();
+    names.filter(|name| !seen.insert(*name)).collect()
+}
+
+/// The rarities `Shop::create` stocks every day
+const SHOP_TIERS: [RodRarity; 4] = [RodRarity::Common, RodRarity::Uncommon, RodRarity::Rare, RodRarity::Epic];
+
+fn validate_fish(fish_data: &FishData, issues: &mut Issues) {
+    if fish_data.fish.is_empty() {
+        issues.error("fish.json", "there are no fish");
+    }
+
+    for name in find_duplicates(fish_data.fish.iter().map(|fish| &fish.name)) {
+        issues.error("fish.json", format!("{} is defined more than once", name));
+    }
+
+    for fish in &fish_data.fish {
+        if FishRarity::from_str(fish.min_rarity.as_str()).is_err() {
+            issues.error("fish.json", format!("{} has unknown min_rarity \"{}\"", fish.name, fish.min_rarity));
+        }
+
+        if !(fish.min_weight <= fish.avg_weight && fish.avg_weight <= fish.max_weight) {
+            issues.error("fish.json", format!("{} must have min_weight <= avg_weight <= max_weight, but has {} / {} / {}",
+                                              fish.name, fish.min_weight, fish.avg_weight, fish.max_weight));
+        } else if fish.min_weight == fish.max_weight {
+            issues.error("fish.json", format!("{} has the same min_weight and max_weight, so no weight can be rolled", fish.name));
+        }
+
+        for window in &fish.active {
+            if let Err(reason) = window.check() {
+                issues.error("fish.json", format!("{}: {}", fish.name, reason));
+            }
+        }
+    }
+}
+
+/// Every depth a rod can fish at, with and without each modifier
+fn reachable_depths(rod: &BaseRod, rod_data: &RodData) -> Vec<i64> {
+    let mut depths = vec![rod.depth as i64];
+    depths.extend(rod_data.modifiers.iter().map(|modifier| rod.depth as i64 + modifier.depth as i64));
+    depths
+}
+
+fn validate_rods(rod_data: &RodData, issues: &mut Issues) {
+    for name in find_duplicates(rod_data.rods.iter().map(|rod| &rod.name)) {
+        issues.error("rods.json", format!("rod {} is defined more than once", name));
+    }
+
+    for name in find_duplicates(rod_data.modifiers.iter().map(|modifier| &modifier.name)) {
+        issues.error("rods.json", format!("modifier {} is defined more than once", name));
+    }
+
+    if rod_data.modifiers.is_empty() {
+        issues.error("rods.json", "there are no modifiers to roll when a rod is bought");
+    }
+
+    if rod_data.get_base_by_name(DEFAULT_ROD).is_none() {
+        issues.error("rods.json", format!("the starting rod \"{}\" is missing", DEFAULT_ROD));
+    }
+
+    for rod in &rod_data.rods {
+        let Ok(rarity) = RodRarity::from_str(rod.rarity.as_str()) else {
+            issues.error("rods.json", format!("{} has unknown rarity \"{}\"", rod.name, rod.rarity));
+            continue;
+        };
+
+        if !(0.0..=1.0).contains(&rod.catch_chance) {
+            issues.error("rods.json", format!("{} has a catch_chance of {}, which is outside 0 to 1", rod.name, rod.catch_chance));
+        }
+
+        // only rods bought from the shop ever roll a modifier
+        let obtainable = !matches!(rarity, RodRarity::Unobtainable);
+
+        for modifier in &rod_data.modifiers {
+            let depth = rod.depth as i64 + modifier.depth as i64;
+            let weight_limit = rod.weight_limit as i64 + modifier.weight_limit as i64;
+
+            let mut problems = Vec::new();
+            if depth < 0 {
+                problems.push(format!("a depth of {}", depth));
+            }
+            if weight_limit < 0 {
+                problems.push(format!("a weight limit of {}", weight_limit));
+            }
+            if problems.is_empty() {
+                continue;
+            }
+
+            let message = format!("the {} modifier gives {} {}", modifier.name, rod.name, problems.join(" and "));
+            if obtainable {
+                issues.error("rods.json", message);
+            } else {
+                issues.warn("rods.json", format!("{} (it can't be bought, so it never rolls a modifier)", message));
+            }
+        }
+    }
+
+    for tier in SHOP_TIERS {
+        if !rod_data.rods.iter().any(|rod| rod.get_rarity().get_ident() == tier.get_ident()) {
+            issues.error("rods.json", format!("the shop stocks a {} rod every day, but there are no {} rods", tier, tier));
+        }
+    }
+}
+
+/// Checks fish.json and rods.json for anything that would panic or quietly misbehave at runtime
+pub fn validate(fish_data: &FishData, rod_data: &RodData) -> Vec<Issue> {
+    let mut issues = Issues(Vec::new());
+
+    validate_fish(fish_data, &mut issues);
+    validate_rods(rod_data, &mut issues);
+
+    issues.0
+}
+
+/// Checks bait.json against fish.json
+pub fn validate_bait(bait_data: &BaitData, fish_data: &FishData) -> Vec<Issue> {
+    let mut issues = Issues(Vec::new());
+
+    for name in find_duplicates(bait_data.bait.iter().map(|bait| &bait.name)) {
+        issues.error("bait.json", format!("{} is defined more than once", name));
+    }
+
+    for bait in &bait_data.bait {
+        if bait.quantity == 0 {
+            issues.error("bait.json", format!("{} comes in packs of 0", bait.name));
+        }
+        if bait.catch_time <= 0.0 {
+            issues.error("bait.json", format!("{} has a catch_time of {}, it must be above 0", bait.name, bait.catch_time));
+        }
+        if bait.species_weight <= 0.0 {
+            issues.error("bait.json", format!("{} has a species_weight of {}, it must be above 0", bait.name, bait.species_weight));
+        }
+        for (rarity, weight) in &bait.rarity_weights {
+            if FishRarity::from_str(rarity.as_str()).is_err() {
+                issues.error("bait.json", format!("{} boosts unknown rarity \"{}\"", bait.name, rarity));
+            }
+            if *weight < 0.0 {
+                issues.error("bait.json", format!("{} has a negative weight for {}", bait.name, rarity));
+            }
+        }
+        for species in &bait.species {
+            if fish_data.fish_type_by_name(species.as_str()).is_none() {
+                issues.warn("bait.json", format!("{} attracts {}, which isn't in fish.json", bait.name, species));
+            }
+        }
+    }
+
+    issues.0
+}
+
+/// Checks config.json for a freshness curve the cooler can interpolate along,
+/// and a leveling curve where every level costs at least as much as the last
+pub fn validate_config(config: &Config) -> Vec<Issue> {
+    let mut issues = Issues(Vec::new());
+
+    let freshness = &config.cooler.freshness;
+    for point in freshness {
+        if !(0.0..=1.0).contains(&point.value) {
+            issues.error("config.json", format!("the freshness at {} hours is {}, it must be from 0 to 1",
+                                                point.hours, point.value));
+        }
+    }
+    for pair in freshness.windows(2) {
+        if pair[1].hours.partial_cmp(&pair[0].hours) != Some(Ordering::Greater) {
+            issues.error("config.json", format!("the freshness point at {} hours follows {} hours, they must be increasing",
+                                                pair[1].hours, pair[0].hours));
+        }
+    }
+
+    let leveling = &config.leveling;
+    if !(1.0..=f32::MAX).contains(&leveling.growth) {
+        issues.error("config.json", format!("leveling growth is {}, it must be at least 1", leveling.growth));
+    }
+    if leveling.base_xp == 0 {
+        issues.error("config.json", "leveling base_xp is 0, it must be more than 0");
+    }
+    for (name, xp) in [("catch_xp", leveling.catch_xp), ("xp_per_lb", leveling.xp_per_lb)] {
+        if !(0.0..=f32::MAX).contains(&xp) {
+            issues.error("config.json", format!("leveling {} is {}, it must be a number from 0 up", name, xp));
+        }
+    }
+
+    issues.0
+}
+
+/// Checks multipliers.json for shares of a sum that would take more than the sum
+pub fn validate_multipliers(multiplier_data: &MultiplierData) -> Vec<Issue> {
+    let mut issues = Issues(Vec::new());
+
+    if !(0.0..=1.0).contains(&multiplier_data.transfer_tax) {
+        issues.error("multipliers.json", format!("transfer_tax is {}, it must be from 0 to 1", multiplier_data.transfer_tax));
+    }
+
+    issues.0
+}
+
+/// Checks achievements.json, which refers to rarities by name and to events.json by id
+pub fn validate_achievements(achievement_data: &AchievementData, event_data: &EventData) -> Vec<Issue> {
+    let mut issues = Issues(Vec::new());
+
+    for id in find_duplicates(achievement_data.achievements.iter().map(|achievement| &achievement.id)) {
+        issues.error("achievements.json", format!("{} is defined more than once", id));
+    }
+
+    for achievement in &achievement_data.achievements {
+        match &achievement.trigger {
+            Trigger::CatchRarity { rarity, .. } if FishRarity::from_str(rarity.as_str()).is_err() =>
+                issues.error("achievements.json", format!("{} needs unknown fish rarity \"{}\"", achievement.id, rarity)),
+            Trigger::OwnRod { rarity } if RodRarity::from_str(rarity.as_str()).is_err() =>
+                issues.error("achievements.json", format!("{} needs unknown rod rarity \"{}\"", achievement.id, rarity)),
+            Trigger::Events { event, .. } if event_data.get_by_id(event).is_none() =>
+                issues.error("achievements.json", format!("{} needs unknown event \"{}\"", achievement.id, event)),
+            Trigger::FishCaught { count: 0 } | Trigger::CatchRarity { count: 0, .. }
+            | Trigger::Events { count: 0, .. } | Trigger::LineBreaks { count: 0 } =>
+                issues.warn("achievements.json", format!("{} has a count of 0, so everyone unlocks it", achievement.id)),
+            _ => {}
+        }
+    }
+
+    issues.0
+}
+
+/// Checks locations.json against fish.json and rods.json,
+/// including that every spot a rod can fish has a Common fish in any weather and at any time of day
+pub fn validate_locations(location_data: &LocationData, fish_data: &FishData, rod_data: &RodData,
+                          weather_data: &WeatherData) -> Vec<Issue> {
+    let mut issues = Issues(Vec::new());
+
+    if location_data.locations.is_empty() {
+        issues.error("locations.json", "there are no locations, so there's nowhere to fish");
+        return issues.0;
+    }
+
+    for name in find_duplicates(location_data.locations.iter().map(|location| &location.name)) {
+        issues.error("locations.json", format!("{} is defined more than once", name));
+    }
+
+    for location in &location_data.locations {
+        if location.min_depth > location.max_depth {
+            issues.error("locations.json", format!("{} has a min_depth of {}, deeper than its max_depth of {}",
+                                                   location.name, location.min_depth, location.max_depth));
+        }
+    }
+
+    let home = location_data.home();
+    if let Some(starter) = rod_data.get_base_by_name(DEFAULT_ROD) {
+        if !home.can_fish(starter.depth) {
+            issues.error("locations.json", format!("everyone starts at {} with the {}, which can't reach its min_depth of {}ft",
+                                                   home.name, starter.name, home.min_depth));
+        }
+    }
+
+    for fish in &fish_data.fish {
+        if fish.habitats.is_empty() {
+            issues.warn("fish.json", format!("{} has no habitats, so it's never caught", fish.name));
+        }
+        for habitat in &fish.habitats {
+            match location_data.get_by_name(habitat.as_str()) {
+                None => issues.warn("fish.json", format!("{} lives in {}, which isn't in locations.json", fish.name, habitat)),
+                Some(location) if fish.depth > location.max_depth =>
+                    issues.warn("fish.json", format!("{} lives at {}ft, below the {}ft bottom of {}",
+                                                     fish.name, fish.depth, location.max_depth, location.name)),
+                _ => {}
+            }
+        }
+    }
+
+    let mut checked = HashSet::new();
+    for rod in &rod_data.rods {
+        for depth in reachable_depths(rod, rod_data) {
+            if depth < 0 || !checked.insert(depth) {
+                continue;
+            }
+
+            for location in location_data.locations.iter().filter(|location| location.can_fish(depth as u32)) {
+                let catchable = location.catchable(fish_data, depth as u32);
+                for weather in &weather_data.weather {
+                    for time in &weather_data.times {
+                        // `Fish::random_fish` rerolls until the species fits the rarity, which never ends without a Common fish.
+                        // Seasonal species don't count since they're gone for part of the year.
+                        let has_common = catchable.iter()
+                            .filter(|fish| !fish.is_seasonal())
+                            .filter(|fish| fish.weather.is_empty() || fish.weather.contains(&weather.name))
+                            .filter(|fish| fish.times.is_empty() || fish.times.contains(&time.name))
+                            .any(|fish| fish.min_rarity == FishRarity::Common.to_string());
+                        if !has_common {
+                            issues.error("fish.json", format!("no Common fish lives at or above {}ft in {} during {} {}, which {} can fish at",
+                                                              depth, location.name, weather.name, time.name, rod.name));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    issues.0
+}
+
+fn validate_effects(name: &str, effects: &Effects, fish_data: &FishData, issues: &mut Issues) {
+    for (rarity, weight) in &effects.rarity_weights {
+        if FishRarity::from_str(rarity.as_str()).is_err() {
+            issues.error("weather.json", format!("{} boosts unknown rarity \"{}\"", name, rarity));
+        }
+        if *weight < 0.0 {
+            issues.error("weather.json", format!("{} has a negative weight for {}", name, rarity));
+        }
+    }
+    for (species, weight) in &effects.species_weights {
+        if fish_data.fish_type_by_name(species.as_str()).is_none() {
+            issues.warn("weather.json", format!("{} attracts {}, which isn't in fish.json", name, species));
+        }
+        if *weight < 0.0 {
+            issues.error("weather.json", format!("{} has a negative weight for {}", name, species));
+        }
+    }
+    if effects.catch_time <= 0.0 {
+        issues.error("weather.json", format!("{} has a catch_time of {}, it must be above 0", name, effects.catch_time));
+    }
+    if !(0.0..=1.0).contains(&effects.line_break_chance) {
+        issues.error("weather.json", format!("{} has a line_break_chance of {}, which is outside 0 to 1", name, effects.line_break_chance));
+    }
+}
+
+/// Checks weather.json, and the weather and times of day fish.json refers to
+pub fn validate_weather(weather_data: &WeatherData, fish_data: &FishData) -> Vec<Issue> {
+    let mut issues = Issues(Vec::new());
+
+    if weather_data.weather.iter().all(|weather| weather.chance == 0) {
+        issues.error("weather.json", "no weather has a chance above 0, so none can be rolled");
+    }
+    if weather_data.times.is_empty() {
+        issues.error("weather.json", "there are no times of day");
+    }
+    if weather_data.change_every == 0 {
+        issues.error("weather.json", "change_every must be at least 1 minute");
+    }
+
+    for name in find_duplicates(weather_data.weather.iter().map(|weather| &weather.name)) {
+        issues.error("weather.json", format!("weather {} is defined more than once", name));
+    }
+    for name in find_duplicates(weather_data.times.iter().map(|time| &time.name)) {
+        issues.error("weather.json", format!("time of day {} is defined more than once", name));
+    }
+
+    for weather in &weather_data.weather {
+        validate_effects(weather.name.as_str(), &weather.effects, fish_data, &mut issues);
+    }
+    for time in &weather_data.times {
+        if time.start >= 24 {
+            issues.error("weather.json", format!("{} starts at hour {}, which isn't on a 24 hour clock", time.name, time.start));
+        }
+        validate_effects(time.name.as_str(), &time.effects, fish_data, &mut issues);
+    }
+
+    for fish in &fish_data.fish {
+        for weather in &fish.weather {
+            if !weather_data.weather.iter().any(|known| &known.name == weather) {
+                issues.warn("fish.json", format!("{} only bites in {}, which isn't in weather.json", fish.name, weather));
+            }
+        }
+        for time in &fish.times {
+            if !weather_data.times.iter().any(|known| &known.name == time) {
+                issues.warn("fish.json", format!("{} only bites at {}, which isn't in weather.json", fish.name, time));
+            }
+        }
+    }
+
+    issues.0
+}
+
+/// Checks events.json against the bait, rods and locations its events refer to
+pub fn validate_events(event_data: &EventData, bait_data: &BaitData, rod_data: &RodData,
+                       location_data: &LocationData) -> Vec<Issue> {
+    let mut issues = Issues(Vec::new());
+
+    for id in find_duplicates(event_data.events.iter().map(|event| &event.id)) {
+        issues.error("events.json", format!("event {} is defined more than once", id));
+    }
+
+    for event in &event_data.events {
+        if !(0.0..=100.0).contains(&event.chance) {
+            issues.error("events.json", format!("{} has a chance of {}%, it must be from 0 to 100", event.id, event.chance));
+        }
+        if event.effects.is_empty() {
+            issues.warn("events.json", format!("{} has no effects, so it only posts a message", event.id));
+        }
+        if event.embed.colour().is_none() {
+            issues.error("events.json", format!("{} has colour \"{}\", which isn't a hex RGB colour", event.id, event.embed.colour));
+        }
+        if let Some(image) = &event.embed.image {
+            if !Path::new("./assets").join(image).exists() {
+                issues.warn("events.json", format!("{} shows {}, which isn't in ./assets", event.id, image));
+            }
+        }
+
+        for effect in &event.effects {
+            match effect {
+                Effect::MultiplyValue { multiplier } if *multiplier <= 0.0 => {
+                    issues.error("events.json", format!("{} multiplies value by {}, it must be above 0", event.id, multiplier));
+                }
+                Effect::BonusBait { bait, count } => {
+                    if bait_data.get_by_name(bait.as_str()).is_none() {
+                        issues.error("events.json", format!("{} gives {}, which isn't in bait.json", event.id, bait));
+                    }
+                    if *count == 0 {
+                        issues.warn("events.json", format!("{} gives 0 {}", event.id, bait));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        for rod in &event.conditions.rods {
+            if rod_data.get_base_by_name(rod.as_str()).is_none() {
+                issues.warn("events.json", format!("{} needs the {}, which isn't in rods.json", event.id, rod));
+            }
+        }
+        for location in &event.conditions.locations {
+            if location_data.get_by_name(location.as_str()).is_none() {
+                issues.warn("events.json", format!("{} happens at the {}, which isn't in locations.json", event.id, location));
+            }
+        }
+    }
+
+    issues.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{validate_achievements, validate_config, Severity};
+    use crate::data::achievements::{Achievement, Reward, Trigger};
+    use crate::data::config::Config;
+    use crate::data::cooler::{CoolerConfig, FreshnessPoint};
+    use crate::engine::testing::game_data;
+
+    fn config(freshness: Vec<FreshnessPoint>) -> Config {
+        let mut config = serde_json::from_str::<Config>(r#"{ "motd": "", "debug_mode": false }"#).unwrap();
+        config.cooler.freshness = freshness;
+        config
+    }
+
+    #[test]
+    fn the_default_freshness_curve_is_valid() {
+        assert!(validate_config(&config(CoolerConfig::default().freshness)).is_empty());
+    }
+
+    #[test]
+    fn freshness_curves_must_rise_in_hours_and_stay_in_range() {
+        let issues = validate_config(&config(vec![
+            FreshnessPoint { hours: 0.0, value: 1.0 },
+            FreshnessPoint { hours: 24.0, value: 1.5 },
+            FreshnessPoint { hours: 24.0, value: 0.5 },
+            FreshnessPoint { hours: 6.0, value: -0.1 },
+        ]));
+
+        assert_eq!(issues.len(), 4);
+        assert!(issues.iter().all(|issue| issue.severity == Severity::Error));
+    }
+
+    #[test]
+    fn achievements_must_name_real_events() {
+        let game_data = game_data();
+        assert!(validate_achievements(&game_data.achievements, &game_data.events).is_empty());
+
+        let mut achievements = game_data.achievements.clone();
+        achievements.achievements.push(Achievement {
+            id: "kraken_tamer".to_string(),
+            name: "Kraken Tamer".to_string(),
+            description: "Meet the kraken".to_string(),
+            trigger: Trigger::Events { event: "kraken".to_string(), count: 1 },
+            reward: Reward::default(),
+        });
+
+        let issues = validate_achievements(&achievements, &game_data.events);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].severity, Severity::Error);
+        assert!(issues[0].message.contains("kraken"));
+    }
+
+    #[test]
+    fn leveling_must_grow_from_a_positive_base() {
+        let mut config = config(CoolerConfig::default().freshness);
+        assert!(validate_config(&config).is_empty());
+
+        config.leveling.growth = 0.0;
+        config.leveling.base_xp = 0;
+        config.leveling.catch_xp = f32::NAN;
+        assert_eq!(validate_config(&config).len(), 3);
+
+        config.leveling.growth = f32::NAN;
+        config.leveling.base_xp = 100;
+        config.leveling.catch_xp = 10.0;
+        assert_eq!(validate_config(&config).len(), 1);
+    }
+}
@@ -0,0 +1,215 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use chrono::{DateTime, FixedOffset, Local, NaiveDate, Timelike};
+use rand::Rng;
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+use crate::data::fish::{FishRarity, FishType};
+use crate::data::rng::weather_rng;
+use crate::data::userfile::store;
+use crate::error::AnglerError;
+
+fn one() -> f32 {
+    1.0
+}
+
+/// How a weather or time of day changes fishing. Every effect defaults to doing nothing.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Effects {
+    /// Multiplies the roll weight of each named `FishRarity`
+    #[serde(default)]
+    pub rarity_weights: HashMap<String, f32>,
+    /// Multiplies how likely each named species is to bite
+    #[serde(default)]
+    pub species_weights: HashMap<String, f32>,
+    /// Multiplies the time until a bite
+    #[serde(default = "one")]
+    pub catch_time: f32,
+    /// Chance from 0 to 1 that the line snaps, whatever the fish weighs
+    #[serde(default)]
+    pub line_break_chance: f32,
+}
+
+impl Effects {
+    /// Whether these effects leave the fish that bites untouched
+    fn is_calm(&self) -> bool {
+        self.rarity_weights.is_empty() && self.species_weights.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Weather {
+    pub name: String,
+    pub emoji: String,
+    pub description: String,
+    /// Relative chance of this weather rolling
+    pub chance: u32,
+    #[serde(flatten)]
+    pub effects: Effects,
+}
+
+impl Display for Weather {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.emoji, self.name)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimeOfDay {
+    pub name: String,
+    pub emoji: String,
+    pub description: String,
+    /// The hour of the guild's local time this part of the day starts at
+    pub start: u32,
+    #[serde(flatten)]
+    pub effects: Effects,
+}
+
+impl Display for TimeOfDay {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.emoji, self.name)
+    }
+}
+
+/// The weather, time of day and date a line was cast in
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Conditions {
+    pub weather: Weather,
+    pub time: TimeOfDay,
+    /// The guild's local date. Only matters while the fish is rolled, so it isn't stored with the cast.
+    #[serde(skip)]
+    pub date: NaiveDate,
+}
+
+impl Conditions {
+    /// Whether the species' availability rules let it bite right now
+    pub fn allows(&self, fish: &FishType) -> bool {
+        (fish.weather.is_empty() || fish.weather.contains(&self.weather.name))
+            && (fish.times.is_empty() || fish.times.contains(&self.time.name))
+            && fish.is_active(self.date)
+    }
+
+    /// Whether the conditions leave rarity and species weights alone
+    pub fn is_calm(&self) -> bool {
+        self.weather.effects.is_calm() && self.time.effects.is_calm()
+    }
+
+    pub fn rarity_weight(&self, rarity: &FishRarity) -> f32 {
+        let rarity = rarity.to_string();
+        [&self.weather.effects, &self.time.effects].iter()
+            .map(|effects| effects.rarity_weights.get(rarity.as_str()).copied().unwrap_or(1.0))
+            .product()
+    }
+
+    pub fn species_weight(&self, species: &str) -> f32 {
+        [&self.weather.effects, &self.time.effects].iter()
+            .map(|effects| effects.species_weights.get(species).copied().unwrap_or(1.0))
+            .product()
+    }
+
+    pub fn catch_time(&self) -> f32 {
+        self.weather.effects.catch_time * self.time.effects.catch_time
+    }
+
+    /// Rolls whether the line snaps. Calm conditions never touch the rng, so they don't shift later rolls.
+    pub fn snaps_line<R: Rng + ?Sized>(&self, rng: &mut R) -> bool {
+        let chance = self.weather.effects.line_break_chance + self.time.effects.line_break_chance;
+        chance > 0.0 && rng.gen::<f32>() < chance
+    }
+}
+
+impl Conditions {
+    /// A compact `weather/time/date` form for the cast logs, read back by `WeatherData::parse_conditions`
+    pub fn log_key(&self) -> String {
+        format!("{}/{}/{}", self.weather.name, self.time.name, self.date)
+    }
+}
+
+impl Display for Conditions {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}, {}", self.weather, self.time)
+    }
+}
+
+/// The time at `timestamp` for a guild `utc_offset` minutes ahead of UTC
+pub fn local_time(utc_offset: i32, timestamp: i64) -> Option<DateTime<FixedOffset>> {
+    let offset = FixedOffset::east_opt(utc_offset * 60)?;
+    DateTime::from_timestamp(timestamp, 0).map(|time| time.with_timezone(&offset))
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeatherData {
+    /// Minutes between weather changes
+    pub change_every: u32,
+    pub weather: Vec<Weather>,
+    /// The parts of the day, each lasting until the next one starts
+    pub times: Vec<TimeOfDay>,
+}
+
+impl WeatherData {
+    pub fn load() -> Result<Self, AnglerError> {
+        AnglerError::load_json("./data/weather.json")
+    }
+
+    fn period_length(&self) -> i64 {
+        self.change_every.max(1) as i64 * 60
+    }
+
+    /// The guild's weather at `timestamp`. Every guild rolls its own, the same for everyone in it.
+    pub fn weather_at(&self, guild_id: u64, timestamp: i64) -> &Weather {
+        let period = timestamp.div_euclid(self.period_length());
+        let mut rng = weather_rng(guild_id, period);
+
+        self.weather.choose_weighted(&mut rng, |weather| weather.chance)
+            .unwrap_or(&self.weather[0])
+    }
+
+    /// The part of the day at `timestamp`, for a guild `utc_offset` minutes ahead of UTC
+    pub fn time_at(&self, utc_offset: i32, timestamp: i64) -> &TimeOfDay {
+        let hour = local_time(utc_offset, timestamp).map(|time| time.hour()).unwrap_or(0);
+
+        // the latest part to have started today, or the last one from yesterday if none has yet
+        self.times.iter()
+            .filter(|time| time.start <= hour)
+            .max_by_key(|time| time.start)
+            .or_else(|| self.times.iter().max_by_key(|time| time.start))
+            .unwrap_or(&self.times[0])
+    }
+
+    pub fn conditions(&self, guild_id: u64, utc_offset: i32, timestamp: i64) -> Conditions {
+        Conditions {
+            weather: self.weather_at(guild_id, timestamp).clone(),
+            time: self.time_at(utc_offset, timestamp).clone(),
+            date: local_time(utc_offset, timestamp).map(|time| time.date_naive()).unwrap_or_default(),
+        }
+    }
+
+    /// Reads back conditions logged with `Conditions::log_key`
+    pub fn parse_conditions(&self, key: &str) -> Option<Conditions> {
+        let mut parts = key.split('/').map(str::trim);
+        let (weather, time, date) = (parts.next()?, parts.next()?, parts.next()?);
+
+        Some(Conditions {
+            weather: self.weather.iter().find(|known| known.name.eq_ignore_ascii_case(weather))?.clone(),
+            time: self.times.iter().find(|known| known.name.eq_ignore_ascii_case(time))?.clone(),
+            date: NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()?,
+        })
+    }
+
+    /// The conditions in the guild right now, in its own timezone
+    pub fn current(&self, guild_id: u64) -> Conditions {
+        let utc_offset = store().read_guild(guild_id).utc_offset;
+        self.conditions(guild_id, utc_offset, Local::now().timestamp())
+    }
+
+    /// The guild's next `count` weather changes, as (timestamp, weather)
+    pub fn forecast(&self, guild_id: u64, timestamp: i64, count: usize) -> Vec<(i64, &Weather)> {
+        let length = self.period_length();
+        let next = (timestamp.div_euclid(length) + 1) * length;
+
+        (0..count as i64)
+            .map(|i| next + i * length)
+            .map(|start| (start, self.weather_at(guild_id, start)))
+            .collect()
+    }
+}
@@ -0,0 +1,23 @@
+use crate::data::achievements::Achievement;
+use crate::data::registry::GameData;
+use crate::data::userfile::UserValues;
+
+/// Unlocks every achievement the player now qualifies for and pays out the rewards.
+/// Returns the newly unlocked achievements so they can be announced.
+pub fn check_achievements(user: &mut UserValues, game_data: &GameData) -> Vec<Achievement> {
+    let unlocked = game_data.achievements.achievements.iter()
+        .filter(|achievement| !user.achievements.contains(&achievement.id))
+        .filter(|achievement| achievement.is_met(user, game_data))
+        .cloned()
+        .collect::<Vec<Achievement>>();
+
+    for achievement in &unlocked {
+        user.achievements.push(achievement.id.clone());
+        user.earn(achievement.reward.money);
+        if let Some(title) = &achievement.reward.title {
+            user.title = Some(title.clone());
+        }
+    }
+
+    unlocked
+}
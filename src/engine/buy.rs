@@ -0,0 +1,159 @@
+use std::fmt::Display;
+use rand::Rng;
+use crate::data::registry::GameData;
+use crate::data::bait::{Bait, BaitStack};
+use crate::data::rods::Rod;
+use crate::data::shop::Shop;
+use crate::data::userfile::{OwnedRod, UserValues};
+
+pub enum BuyError {
+    NoMoney,
+    InvalidRod,
+    InvalidBait,
+    Casting,
+    /// The rod needs a higher angler level
+    Level(u32),
+}
+
+impl Display for BuyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BuyError::NoMoney => write!(f, "You don't have enough money to buy this rod!"),
+            BuyError::InvalidRod => write!(f, "That rod is no longer available!"),
+            BuyError::InvalidBait => write!(f, "That bait is no longer available!"),
+            BuyError::Casting => write!(f, "Please wait until your cast is finished to buy a new rod!"),
+            BuyError::Level(level) => write!(f, "You need to be level {} to buy this rod!", level),
+        }
+    }
+}
+
+pub struct Purchase {
+    pub rod: Rod,
+    pub cost: u32,
+}
+
+/// Buys the rod in `spot` of today's shop, rolling it a modifier, and equips it
+pub fn buy_rod<R: Rng + ?Sized>(user: &mut UserValues, shop: &Shop, spot: usize, game_data: &GameData,
+                                rng: &mut R) -> Result<Purchase, BuyError> {
+    let rod_data = &game_data.rods;
+
+    let Some(base_rod) = shop.rods.get(spot).and_then(|name| rod_data.get_base_by_name(name)) else {
+        return Err(BuyError::InvalidRod);
+    };
+
+    if user.cast {
+        return Err(BuyError::Casting);
+    }
+
+    if game_data.config.leveling.level(user.xp) < base_rod.required_level {
+        return Err(BuyError::Level(base_rod.required_level));
+    }
+
+    let cost = base_rod.get_cost(&game_data.multipliers);
+
+    // ensure the user has enough money to buy the rod
+    if user.money < cost {
+        return Err(BuyError::NoMoney);
+    }
+
+    // generate the rod
+    let rod = Rod {
+        base: base_rod,
+        modifier: rod_data.generate_modifier(rng),
+    };
+
+    // remove the money from the user
+    user.money -= cost;
+
+    // add the rod to the user's inventory
+    user.rods.push(OwnedRod::new(&rod));
+    user.equipped = user.rods.len() - 1;
+
+    Ok(Purchase { rod, cost })
+}
+
+pub struct BaitPurchase {
+    pub bait: Bait,
+    pub cost: u32,
+}
+
+/// Buys one pack of the bait in `spot` of today's bait stock
+pub fn buy_bait(user: &mut UserValues, shop: &Shop, spot: usize, game_data: &GameData) -> Result<BaitPurchase, BuyError> {
+    let Some(bait) = shop.bait.get(spot).and_then(|name| game_data.bait.get_by_name(name.as_str())) else {
+        return Err(BuyError::InvalidBait);
+    };
+
+    if user.money < bait.cost {
+        return Err(BuyError::NoMoney);
+    }
+    user.money -= bait.cost;
+
+    match user.bait.iter_mut().find(|stack| stack.name == bait.name) {
+        Some(stack) => stack.count += bait.quantity,
+        None => user.bait.push(BaitStack { name: bait.name.clone(), count: bait.quantity }),
+    }
+
+    Ok(BaitPurchase { bait: bait.clone(), cost: bait.cost })
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use crate::data::rng::GameRng;
+    use crate::data::shop::Shop;
+    use crate::data::userfile::UserValues;
+    use crate::engine::testing::game_data;
+    use super::{buy_bait, buy_rod, BuyError};
+
+    fn shop() -> Shop {
+        Shop {
+            refresh: 0,
+            seed: 0,
+            rods: vec!["Fiberglass Casting Rod".to_string(), "Composite Casting Rod".to_string()],
+            bait: vec!["Shiny Spinner".to_string()],
+        }
+    }
+
+    fn try_buy_rod(user: &mut UserValues, spot: usize) -> Result<u32, BuyError> {
+        buy_rod(user, &shop(), spot, game_data(), &mut GameRng::seed_from_u64(1)).map(|purchase| purchase.cost)
+    }
+
+    #[test]
+    fn buying_a_rod_equips_it() {
+        let mut user = UserValues { money: 100_000, ..UserValues::default() };
+
+        let cost = try_buy_rod(&mut user, 0).ok().expect("the rod should be bought");
+        assert_eq!(user.money, 100_000 - cost);
+        assert_eq!(user.rods.len(), 2);
+        assert_eq!(user.equipped, 1);
+        assert_eq!(user.rods[1].name, "Fiberglass Casting Rod");
+    }
+
+    #[test]
+    fn rod_purchases_are_checked() {
+        let mut user = UserValues::default();
+        assert!(matches!(try_buy_rod(&mut user, 5), Err(BuyError::InvalidRod)));
+        assert!(matches!(try_buy_rod(&mut user, 0), Err(BuyError::NoMoney)));
+        assert!(matches!(try_buy_rod(&mut user, 1), Err(BuyError::Level(3))));
+
+        let mut user = UserValues { money: 100_000, cast: true, ..UserValues::default() };
+        assert!(matches!(try_buy_rod(&mut user, 0), Err(BuyError::Casting)));
+        assert_eq!(user.money, 100_000);
+        assert_eq!(user.rods.len(), 1);
+    }
+
+    #[test]
+    fn bait_stacks_up() {
+        let game_data = game_data();
+        let bait = game_data.bait.get_by_name("Shiny Spinner").unwrap();
+        let mut user = UserValues { money: bait.cost * 2, ..UserValues::default() };
+
+        assert!(buy_bait(&mut user, &shop(), 0, game_data).is_ok());
+        assert!(buy_bait(&mut user, &shop(), 0, game_data).is_ok());
+        assert_eq!(user.bait.len(), 1);
+        assert_eq!(user.bait[0].count, bait.quantity * 2);
+
+        assert!(matches!(buy_bait(&mut user, &shop(), 0, game_data), Err(BuyError::NoMoney)));
+        assert!(matches!(buy_bait(&mut user, &shop(), 1, game_data), Err(BuyError::InvalidBait)));
+    }
+}
@@ -0,0 +1,195 @@
+use serde::{Deserialize, Serialize};
+use crate::data::bait::Bait;
+use crate::data::fish::Fish;
+use crate::data::locations::Location;
+use crate::data::registry::GameData;
+use crate::data::rng::cast_rng;
+use crate::data::rods::Rod;
+use crate::data::weather::Conditions;
+use crate::data::userfile::UserValues;
+use crate::error::AnglerError;
+
+/// A line in the water. Everything about the catch is decided the moment it's cast,
+/// except that reeling in the bite replaces `will_catch`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Cast {
+    pub fish: Fish,
+    pub rod: Rod,
+    /// Whether the fish is landed if nobody reels it in, e.g. when it bit while the bot was offline
+    pub will_catch: bool,
+    /// Milliseconds until the catch resolves
+    pub catch_time: u64,
+    /// The seed the cast was rolled from, which also drives the rolls made when it resolves
+    pub seed: u64,
+    /// Skips the line break, escape and event rolls (used for spawned fish)
+    pub override_special: bool,
+    /// The bait on the hook, if any
+    #[serde(default)]
+    pub bait: Option<String>,
+    /// Where the line was cast, missing for casts made before locations existed
+    #[serde(default)]
+    pub location: Option<String>,
+    /// The weather and time of day when the line was cast, missing for spawned fish and older casts
+    #[serde(default)]
+    pub conditions: Option<Conditions>,
+}
+
+pub enum CastOutcome {
+    AlreadyCasting,
+    /// The player asked for bait they have none of
+    NoBait(String),
+    /// The rod can't reach the fish at the player's location
+    TooShallow(Location),
+    /// An event tangled the player's line, and it's untangled at this unix timestamp
+    Tangled(i64),
+    Cast(Box<Cast>),
+}
+
+/// Rolls a cast from its seed. The same seed, rod, bait, location and conditions always give the same cast.
+pub fn roll_cast(rod: &Rod, bait: Option<&Bait>, location: &Location, conditions: &Conditions, game_data: &GameData,
+                 seed: u64) -> Cast {
+    let mut rng = cast_rng(seed);
+
+    let fish = Fish::random_fish(&game_data.fish, rod, bait, location, conditions, &mut rng);
+
+    let will_catch = rod.roll_will_catch(&game_data.multipliers, &mut rng);
+
+    // get the time until catch and convert to miliseconds
+    let catch_time = (fish.catch_time(rod, &game_data.multipliers, bait, &mut rng) * conditions.catch_time() * 1000.0) as u64;
+
+    Cast {
+        fish,
+        rod: rod.clone(),
+        will_catch,
+        catch_time,
+        seed,
+        override_special: false,
+        bait: bait.map(|bait| bait.name.clone()),
+        location: Some(location.name.clone()),
+        conditions: Some(conditions.clone()),
+    }
+}
+
+/// Casts the player's line with their current rod, using up one of `bait` if given.
+/// `now` is a unix timestamp in seconds.
+pub fn cast(user: &mut UserValues, game_data: &GameData, bait: Option<&str>, conditions: &Conditions,
+            seed: u64, now: i64) -> Result<CastOutcome, AnglerError> {
+    if user.cast {
+        return Ok(CastOutcome::AlreadyCasting);
+    }
+    if user.tangled_until > now {
+        return Ok(CastOutcome::Tangled(user.tangled_until));
+    }
+
+    let rod = user.get_rod(&game_data.rods)?;
+
+    let location = game_data.locations.current(user.location.as_deref());
+    if !location.can_fish(rod.get_depth()) {
+        return Ok(CastOutcome::TooShallow(location.clone()));
+    }
+
+    let bait = match bait {
+        Some(name) => {
+            let bait = game_data.bait.get_by_name(name)
+                .ok_or_else(|| AnglerError::UnknownBait(name.to_string()))?;
+
+            let Some(stack) = user.bait.iter_mut().find(|stack| stack.name == bait.name && stack.count > 0) else {
+                return Ok(CastOutcome::NoBait(bait.name.clone()));
+            };
+            stack.count -= 1;
+            user.bait.retain(|stack| stack.count > 0);
+
+            Some(bait)
+        }
+        None => None,
+    };
+
+    user.cast = true;
+
+    Ok(CastOutcome::Cast(Box::new(roll_cast(&rod, bait, location, conditions, game_data, seed))))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::data::bait::BaitStack;
+    use crate::data::userfile::{UserValues, DEFAULT_ROD};
+    use crate::engine::testing::{game_data, rod};
+    use super::{cast, roll_cast, CastOutcome};
+
+    /// A moment with some weather to it, so the conditions have something to change
+    const CAST_AT: i64 = 1_790_000_000;
+
+    #[test]
+    fn same_seed_rolls_the_same_cast() {
+        let game_data = game_data();
+        let bait = game_data.bait.get_by_name("Shiny Spinner");
+
+        for (name, seed) in [(DEFAULT_ROD, 1), ("Composite Fly Rod", 42), ("Titanium Overhead Rod", u64::MAX)] {
+            let rod = rod(name);
+            let location = game_data.locations.deepest_for(rod.get_depth());
+            let conditions = game_data.weather.conditions(7, 0, CAST_AT);
+
+            for bait in [None, bait] {
+                let first = roll_cast(&rod, bait, location, &conditions, game_data, seed);
+                let second = roll_cast(&rod, bait, location, &conditions, game_data, seed);
+
+                assert_eq!(first.fish.fish_type.name, second.fish.fish_type.name);
+                assert_eq!(first.fish.rarity, second.fish.rarity);
+                assert_eq!(first.fish.weight, second.fish.weight);
+                assert_eq!(first.will_catch, second.will_catch);
+                assert_eq!(first.catch_time, second.catch_time);
+            }
+        }
+    }
+
+    #[test]
+    fn different_seeds_roll_different_casts() {
+        let game_data = game_data();
+        let rod = rod(DEFAULT_ROD);
+        let location = game_data.locations.home();
+        let conditions = game_data.weather.conditions(7, 0, CAST_AT);
+
+        let first = roll_cast(&rod, None, location, &conditions, game_data, 0);
+        let differs = (1..50).map(|seed| roll_cast(&rod, None, location, &conditions, game_data, seed))
+            .any(|cast| cast.fish.weight != first.fish.weight || cast.catch_time != first.catch_time);
+        assert!(differs);
+    }
+
+    fn try_cast(user: &mut UserValues, bait: Option<&str>) -> CastOutcome {
+        let game_data = game_data();
+        let conditions = game_data.weather.conditions(7, 0, CAST_AT);
+        cast(user, game_data, bait, &conditions, 1, CAST_AT).expect("the default rod should load")
+    }
+
+    #[test]
+    fn casting_sets_the_line() {
+        let mut user = UserValues::default();
+        assert!(matches!(try_cast(&mut user, None), CastOutcome::Cast(_)));
+        assert!(user.cast);
+        assert!(matches!(try_cast(&mut user, None), CastOutcome::AlreadyCasting));
+    }
+
+    #[test]
+    fn tangled_lines_cant_cast() {
+        let mut user = UserValues { tangled_until: CAST_AT + 60, ..UserValues::default() };
+        assert!(matches!(try_cast(&mut user, None), CastOutcome::Tangled(until) if until == CAST_AT + 60));
+        assert!(!user.cast);
+    }
+
+    #[test]
+    fn bait_is_used_up() {
+        let mut user = UserValues::default();
+        assert!(matches!(try_cast(&mut user, Some("Shiny Spinner")), CastOutcome::NoBait(_)));
+
+        user.bait.push(BaitStack { name: "Shiny Spinner".to_string(), count: 1 });
+        assert!(matches!(try_cast(&mut user, Some("Shiny Spinner")), CastOutcome::Cast(_)));
+        assert!(user.bait.is_empty());
+    }
+
+    #[test]
+    fn shallow_rods_cant_fish_deep_water() {
+        let mut user = UserValues { location: Some("Deep Trench".to_string()), ..UserValues::default() };
+        assert!(matches!(try_cast(&mut user, None), CastOutcome::TooShallow(_)));
+        assert!(!user.cast);
+    }
+}
@@ -0,0 +1,268 @@
+use crate::data::cooler::CooledFish;
+use crate::data::events::Event;
+use crate::data::fish::Fish;
+use crate::data::records::record_catch;
+use crate::data::registry::GameData;
+use crate::data::rng::catch_rng;
+use crate::data::userfile::{HeaviestCatch, UserValues};
+use crate::engine::cast::Cast;
+use crate::engine::events::{apply_event, roll_event};
+
+pub enum CatchOutcome {
+    /// The fish went into the cooler
+    Caught {
+        fish: Fish,
+        /// What the fish would sell for right now
+        value: u32,
+        /// Whether this is the first time the player has caught this species
+        first_catch: bool,
+        xp: u32,
+        /// The new level, if this catch levelled the player up
+        level_up: Option<u32>,
+        /// Whether this beat the player's heaviest or most valuable of the species
+        personal_best: bool,
+        /// The random event that happened as it was landed
+        event: Option<Event>,
+    },
+    /// The fish was caught but the cooler had no room, so it was let go.
+    /// It still counts as caught, but released fish don't set records.
+    CoolerFull {
+        fish: Fish,
+        first_catch: bool,
+        xp: u32,
+        level_up: Option<u32>,
+        event: Option<Event>,
+    },
+    Escaped {
+        fish: Fish,
+    },
+    LineBroke {
+        fish: Fish,
+    },
+    /// An event took the fish before it reached the cooler
+    Stolen {
+        fish: Fish,
+        event: Event,
+    },
+}
+
+impl CatchOutcome {
+    /// The fish the player kept, which is the only kind that counts towards records and tournaments
+    pub fn kept(&self) -> Option<&Fish> {
+        match self {
+            CatchOutcome::Caught { fish, .. } => Some(fish),
+            _ => None,
+        }
+    }
+}
+
+/// Resolves a cast and applies the result to the player, rolling the guild's enabled `events`.
+/// `caught_at` is a unix timestamp in seconds.
+pub fn resolve_catch(user: &mut UserValues, cast: &Cast, game_data: &GameData, events: &[Event],
+                     caught_at: i64) -> CatchOutcome {
+    user.cast = false;
+
+    let fish = cast.fish.clone();
+
+    let mut rng = catch_rng(cast.seed);
+
+    // if the fish is too heavy for the rod the line breaks, and rough weather can snap it anyway
+    let snapped = fish.breaks_line(&cast.rod)
+        || cast.conditions.as_ref().is_some_and(|conditions| conditions.snaps_line(&mut rng));
+    if snapped && !cast.override_special {
+        user.line_breaks += 1;
+        return CatchOutcome::LineBroke { fish };
+    }
+
+    if !cast.will_catch && !cast.override_special {
+        return CatchOutcome::Escaped { fish };
+    }
+
+    let event = if cast.override_special { None } else { roll_event(events, cast, &mut rng).cloned() };
+    if let Some(event) = &event {
+        apply_event(user, event, caught_at);
+        if event.steals() {
+            return CatchOutcome::Stolen { fish, event: event.clone() };
+        }
+    }
+
+    // records go by what the fish itself is worth, events only change what it sells for
+    let value = fish.get_value(game_data).max(1);
+    let value_multiplier = event.as_ref().map_or(1.0, |event| event.value_multiplier());
+
+    let first_catch = !user.has_seen.contains(&fish.fish_type.name);
+    if first_catch {
+        user.has_seen.push(fish.fish_type.name.clone());
+    }
+    user.fish_caught += 1;
+    *user.rarities_caught.entry(fish.rarity.to_string()).or_insert(0) += 1;
+    if user.heaviest_catch.as_ref().is_none_or(|heaviest| fish.weight > heaviest.weight) {
+        user.heaviest_catch = Some(HeaviestCatch { species: fish.fish_type.name.clone(), weight: fish.weight });
+    }
+    let leveling = &game_data.config.leveling;
+    let xp = leveling.xp_for_catch(&fish, first_catch);
+    let old_level = leveling.level(user.xp);
+    user.xp = user.xp.saturating_add(xp);
+    let new_level = leveling.level(user.xp);
+    let level_up = (new_level > old_level).then_some(new_level);
+
+    if user.cooler.len() >= game_data.config.cooler.capacity(&cast.rod) {
+        return CatchOutcome::CoolerFull { fish, first_catch, xp, level_up, event };
+    }
+    // forced catches from `!spawn` don't count towards records
+    let personal_best = !cast.override_special && record_catch(&mut user.records, 0, &fish, value, caught_at);
+    let mut cooled = CooledFish::new(&fish, caught_at);
+    cooled.value_multiplier = value_multiplier;
+    user.cooler.push(cooled);
+
+    let value = ((value as f32 * value_multiplier) as u32).max(1);
+    CatchOutcome::Caught { fish, value, first_catch, xp, level_up, personal_best, event }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::data::cooler::CooledFish;
+    use crate::data::events::Event;
+    use crate::data::userfile::{UserValues, DEFAULT_ROD};
+    use crate::engine::cast::roll_cast;
+    use crate::engine::testing::{cast, fish, game_data, rod};
+    use super::{resolve_catch, CatchOutcome};
+
+    fn describe(outcome: &CatchOutcome) -> String {
+        match outcome {
+            CatchOutcome::Caught { fish, value, xp, .. } => format!("caught {} {}lbs for ${} (+{} XP)", fish, fish.weight, value, xp),
+            CatchOutcome::CoolerFull { fish, .. } => format!("cooler full for {}", fish),
+            CatchOutcome::Escaped { fish } => format!("{} escaped", fish),
+            CatchOutcome::LineBroke { fish } => format!("{} broke the line", fish),
+            CatchOutcome::Stolen { fish, event } => format!("{} stolen by {}", fish, event.id),
+        }
+    }
+
+    #[test]
+    fn resolving_a_cast_is_stable() {
+        let game_data = game_data();
+        let rod = rod(DEFAULT_ROD);
+        let location = game_data.locations.home();
+        let events = game_data.events.enabled(&Default::default());
+
+        // every weather, so the line break roll is covered
+        for (seed, timestamp) in (0..200).zip((0..).step_by(3 * 60 * 60)) {
+            let conditions = game_data.weather.conditions(7, 0, timestamp);
+            let cast = roll_cast(&rod, None, location, &conditions, game_data, seed);
+
+            let (mut first, mut second) = (UserValues::default(), UserValues::default());
+            let first_outcome = resolve_catch(&mut first, &cast, game_data, &events, 0);
+            let second_outcome = resolve_catch(&mut second, &cast, game_data, &events, 0);
+
+            assert_eq!(describe(&first_outcome), describe(&second_outcome));
+            assert_eq!(first.cooler, second.cooler);
+            assert_eq!(first.xp, second.xp);
+            assert_eq!(first.line_breaks, second.line_breaks);
+            assert_eq!(first.events, second.events);
+        }
+    }
+
+    /// An event from events.json that always fires
+    fn certain_event(id: &str) -> Event {
+        let mut event = game_data().events.get_by_id(id).expect("the event should be in events.json").clone();
+        event.chance = 100.0;
+        event
+    }
+
+    #[test]
+    fn heavy_fish_break_the_line() {
+        let cast = cast(rod(DEFAULT_ROD), fish("Bass", 30.0), 1);
+        let mut user = UserValues::default();
+
+        let outcome = resolve_catch(&mut user, &cast, game_data(), &[], 0);
+        assert!(matches!(outcome, CatchOutcome::LineBroke { .. }));
+        assert_eq!(user.line_breaks, 1);
+        assert!(user.cooler.is_empty());
+    }
+
+    #[test]
+    fn missed_fish_escape() {
+        let mut cast = cast(rod(DEFAULT_ROD), fish("Bass", 10.0), 1);
+        cast.will_catch = false;
+        let mut user = UserValues::default();
+
+        let outcome = resolve_catch(&mut user, &cast, game_data(), &[], 0);
+        assert!(matches!(outcome, CatchOutcome::Escaped { .. }));
+        assert_eq!(user.fish_caught, 0);
+    }
+
+    #[test]
+    fn stealing_events_take_the_fish() {
+        let cast = cast(rod(DEFAULT_ROD), fish("Bass", 10.0), 1);
+        let mut user = UserValues::default();
+
+        let outcome = resolve_catch(&mut user, &cast, game_data(), &[certain_event("turtle")], 0);
+        assert!(matches!(outcome, CatchOutcome::Stolen { ref event, .. } if event.id == "turtle"));
+        assert_eq!(user.events.get("turtle"), Some(&1));
+        assert!(user.cooler.is_empty());
+    }
+
+    #[test]
+    fn full_coolers_let_the_fish_go() {
+        let game_data = game_data();
+        let rod = rod(DEFAULT_ROD);
+        let capacity = game_data.config.cooler.capacity(&rod);
+        let cast = cast(rod, fish("Bass", 10.0), 1);
+
+        let cooler = vec![CooledFish::new(&fish("Perch", 2.0), 0); capacity];
+        let mut user = UserValues { cooler, ..UserValues::default() };
+
+        let outcome = resolve_catch(&mut user, &cast, game_data, &[], 0);
+        assert!(matches!(outcome, CatchOutcome::CoolerFull { .. }));
+        assert_eq!(user.cooler.len(), capacity);
+        assert_eq!(user.fish_caught, 1);
+
+        // released fish don't set personal, server or tournament records
+        assert!(outcome.kept().is_none());
+        assert!(user.records.is_empty());
+    }
+
+    #[test]
+    fn caught_fish_go_in_the_cooler() {
+        let cast = cast(rod(DEFAULT_ROD), fish("Bass", 10.0), 1);
+        let mut user = UserValues::default();
+
+        let outcome = resolve_catch(&mut user, &cast, game_data(), &[], 100);
+        assert_eq!(outcome.kept().map(|fish| fish.fish_type.name.as_str()), Some("Bass"));
+        assert!(user.records.contains_key("Bass"));
+        let CatchOutcome::Caught { first_catch, xp, .. } = outcome else {
+            panic!("the fish should have been caught");
+        };
+        assert!(first_catch);
+        assert_eq!(user.xp, xp);
+        assert_eq!(user.cooler, vec![CooledFish::new(&cast.fish, 100)]);
+        assert!(user.has_seen.contains(&"Bass".to_string()));
+    }
+
+    #[test]
+    fn value_events_raise_the_sale_price() {
+        let game_data = game_data();
+        let mut cast = cast(rod(DEFAULT_ROD), fish("Cod", 10.0), 1);
+        cast.location = Some("Open Ocean".to_string());
+        let mut user = UserValues::default();
+
+        let outcome = resolve_catch(&mut user, &cast, game_data, &[certain_event("clown_fish")], 0);
+        let CatchOutcome::Caught { value, event: Some(event), .. } = outcome else {
+            panic!("the fish should have been caught with the event");
+        };
+        assert_eq!(event.id, "clown_fish");
+        assert_eq!(value, cast.fish.get_value(game_data).max(1) * 2);
+        assert_eq!(user.cooler[0].value_multiplier, 2.0);
+    }
+
+    #[test]
+    fn spawned_fish_skip_the_special_rolls() {
+        let mut cast = cast(rod(DEFAULT_ROD), fish("Bass", 30.0), 1);
+        cast.will_catch = false;
+        cast.override_special = true;
+        let mut user = UserValues::default();
+
+        let outcome = resolve_catch(&mut user, &cast, game_data(), &[certain_event("turtle")], 0);
+        assert!(matches!(outcome, CatchOutcome::Caught { event: None, .. }));
+    }
+}
@@ -0,0 +1,100 @@
+use std::fmt::Display;
+use crate::data::contracts::{Contract, ContractBoard, ContractKind};
+use crate::data::fish::Fish;
+use crate::data::userfile::UserValues;
+
+/// Something the player did that may count towards a contract
+pub enum ContractEvent<'a> {
+    Catch(&'a Fish),
+    Earn(u32),
+}
+
+/// Adds the event to the player's progress on today's contracts.
+/// Returns the contracts it finished, which still have to be claimed.
+pub fn track_contracts(user: &mut UserValues, board: &ContractBoard, event: ContractEvent) -> Vec<Contract> {
+    user.contracts.sync(board);
+
+    let mut finished = Vec::new();
+    for (contract, progress) in board.contracts.iter().zip(user.contracts.progress.iter_mut()) {
+        let gained = match (&contract.kind, &event) {
+            (ContractKind::CatchRarity { rarity, .. }, ContractEvent::Catch(fish)) =>
+                (fish.rarity.ident() >= rarity.ident()) as u32,
+            (ContractKind::CatchSpecies { species, weight }, ContractEvent::Catch(fish)) =>
+                (fish.fish_type.name == *species && fish.weight > *weight as f32) as u32,
+            (ContractKind::Earn { .. }, ContractEvent::Earn(amount)) => *amount,
+            _ => 0,
+        };
+        if gained > 0 && *progress < contract.goal() && *progress + gained >= contract.goal() {
+            finished.push(contract.clone());
+        }
+        *progress = (*progress + gained).min(contract.goal());
+    }
+
+    finished
+}
+
+pub enum ClaimError {
+    NothingToClaim,
+}
+
+impl Display for ClaimError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ClaimError::NothingToClaim => write!(f, "You haven't finished any contracts yet! Check your progress with `/contracts`."),
+        }
+    }
+}
+
+pub struct Claim {
+    pub contracts: Vec<Contract>,
+    pub reward: u32,
+}
+
+/// Pays out every finished contract that hasn't been claimed yet
+pub fn claim_contracts(user: &mut UserValues, board: &ContractBoard) -> Result<Claim, ClaimError> {
+    user.contracts.sync(board);
+
+    let mut claim = Claim { contracts: Vec::new(), reward: 0 };
+    for (i, contract) in board.contracts.iter().enumerate() {
+        if user.contracts.claimed[i] || user.contracts.progress[i] < contract.goal() {
+            continue;
+        }
+        user.contracts.claimed[i] = true;
+        claim.reward += contract.reward;
+        claim.contracts.push(contract.clone());
+    }
+
+    if claim.contracts.is_empty() {
+        return Err(ClaimError::NothingToClaim);
+    }
+
+    user.earn(claim.reward);
+    Ok(claim)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::data::contracts::{Contract, ContractBoard, ContractKind};
+    use crate::data::userfile::UserValues;
+    use super::{claim_contracts, track_contracts, ContractEvent};
+
+    #[test]
+    fn contract_rewards_count_as_earnings() {
+        let board = ContractBoard {
+            refresh: 1,
+            seed: 0,
+            contracts: vec![Contract { kind: ContractKind::Earn { amount: 100 }, reward: 50 }],
+        };
+        let mut user = UserValues { money: 100, total_earned: 100, ..UserValues::default() };
+
+        assert_eq!(track_contracts(&mut user, &board, ContractEvent::Earn(100)).len(), 1);
+        let claim = claim_contracts(&mut user, &board).ok().expect("the contract should be finished");
+        assert_eq!(claim.reward, 50);
+        assert_eq!(user.money, 150);
+        assert_eq!(user.total_earned, 150);
+
+        // lifetime earnings stop at the cap instead of wrapping around
+        user.earn(u32::MAX);
+        assert_eq!((user.money, user.total_earned), (u32::MAX, u32::MAX));
+    }
+}
@@ -0,0 +1,29 @@
+use rand::Rng;
+use crate::data::bait::BaitStack;
+use crate::data::events::{Effect, Event};
+use crate::data::userfile::UserValues;
+use crate::engine::cast::Cast;
+
+/// Rolls each event that applies to the cast's fish in order, returning the first to fire
+pub fn roll_event<'a, R: Rng + ?Sized>(events: &'a [Event], cast: &Cast, rng: &mut R) -> Option<&'a Event> {
+    events.iter()
+        .filter(|event| event.applies_to(&cast.fish, cast.rod.base.name.as_str(), cast.location.as_deref()))
+        .find(|event| rng.gen_range(0.0..100.0) < event.chance)
+}
+
+/// Applies the effects that change the player rather than the fish.
+/// Stealing and value multipliers are handled by `resolve_catch`. `now` is a unix timestamp in seconds.
+pub fn apply_event(user: &mut UserValues, event: &Event, now: i64) {
+    *user.events.entry(event.id.clone()).or_insert(0) += 1;
+
+    for effect in &event.effects {
+        match effect {
+            Effect::BonusBait { bait, count } => match user.bait.iter_mut().find(|stack| &stack.name == bait) {
+                Some(stack) => stack.count += count,
+                None => user.bait.push(BaitStack { name: bait.clone(), count: *count }),
+            },
+            Effect::ExtraCastTime { seconds } => user.tangled_until = user.tangled_until.max(now + *seconds as i64),
+            Effect::Steal | Effect::MultiplyValue { .. } => {}
+        }
+    }
+}
@@ -0,0 +1,125 @@
+use std::fmt::Display;
+use crate::data::registry::GameData;
+use crate::data::rods::Rod;
+use crate::data::userfile::UserValues;
+use crate::error::AnglerError;
+
+pub enum InventoryError {
+    InvalidRod,
+    Casting,
+    Equipped,
+    /// rods.json no longer has this rod
+    Data(AnglerError),
+}
+
+impl Display for InventoryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InventoryError::InvalidRod => write!(f, "You don't have a rod in that slot! Check your `/inventory`."),
+            InventoryError::Casting => write!(f, "Please wait until your cast is finished to change rods!"),
+            InventoryError::Equipped => write!(f, "You can't sell the rod you're using! Equip another one first."),
+            InventoryError::Data(e) => write!(f, "{}", e.user_message()),
+        }
+    }
+}
+
+pub struct Sale {
+    pub rod: Rod,
+    pub value: u32,
+}
+
+/// Switches the rod used for casting to inventory `slot`
+pub fn equip(user: &mut UserValues, slot: usize, game_data: &GameData) -> Result<Rod, InventoryError> {
+    if user.cast {
+        return Err(InventoryError::Casting);
+    }
+
+    let Some(owned) = user.rods.get(slot) else {
+        return Err(InventoryError::InvalidRod);
+    };
+    let rod = owned.to_rod(&game_data.rods).map_err(InventoryError::Data)?;
+
+    user.equipped = slot;
+
+    Ok(rod)
+}
+
+/// Sells the rod in inventory `slot` back to the shop at its resale value
+pub fn sell_rod(user: &mut UserValues, slot: usize, game_data: &GameData) -> Result<Sale, InventoryError> {
+    if user.cast {
+        return Err(InventoryError::Casting);
+    }
+
+    let Some(owned) = user.rods.get(slot) else {
+        return Err(InventoryError::InvalidRod);
+    };
+
+    // the equipped rod can't go, which also means nobody is ever left without a rod
+    if slot == user.equipped {
+        return Err(InventoryError::Equipped);
+    }
+
+    let rod = owned.to_rod(&game_data.rods).map_err(InventoryError::Data)?;
+    let value = rod.base.get_resale_value(&game_data.multipliers);
+
+    user.rods.remove(slot);
+    if slot < user.equipped {
+        user.equipped -= 1;
+    }
+    user.earn(value);
+
+    Ok(Sale { rod, value })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::data::userfile::{OwnedRod, UserValues, DEFAULT_ROD};
+    use crate::engine::testing::{game_data, rod};
+    use super::{equip, sell_rod, InventoryError};
+
+    /// A player with the starter rod and two more, using the last one
+    fn user() -> UserValues {
+        let mut user = UserValues::default();
+        user.rods.push(OwnedRod::new(&rod("Fiberglass Casting Rod")));
+        user.rods.push(OwnedRod::new(&rod("Fiberglass Spinning Rod")));
+        user.equipped = 2;
+        user
+    }
+
+    #[test]
+    fn equipping_switches_rods() {
+        let mut user = user();
+        let equipped = equip(&mut user, 0, game_data()).ok().expect("the starter rod should equip");
+        assert_eq!(equipped.base.name, DEFAULT_ROD);
+        assert_eq!(user.equipped, 0);
+
+        assert!(matches!(equip(&mut user, 3, game_data()), Err(InventoryError::InvalidRod)));
+        user.cast = true;
+        assert!(matches!(equip(&mut user, 1, game_data()), Err(InventoryError::Casting)));
+        assert_eq!(user.equipped, 0);
+    }
+
+    #[test]
+    fn selling_keeps_the_equipped_rod() {
+        let game_data = game_data();
+        let mut user = user();
+
+        assert!(matches!(sell_rod(&mut user, 2, game_data), Err(InventoryError::Equipped)));
+
+        let sale = sell_rod(&mut user, 1, game_data).ok().expect("an unequipped rod should sell");
+        assert_eq!(sale.value, rod("Fiberglass Casting Rod").base.get_resale_value(&game_data.multipliers));
+        assert_eq!(user.money, sale.value);
+        assert_eq!(user.total_earned, sale.value);
+        assert_eq!(user.rods.len(), 2);
+        // the equipped rod moved down a slot and is still the one in use
+        assert_eq!(user.equipped, 1);
+        assert_eq!(user.rods[user.equipped].name, "Fiberglass Spinning Rod");
+    }
+
+    #[test]
+    fn washed_away_rods_cant_be_equipped() {
+        let mut user = user();
+        user.rods.push(OwnedRod { name: "Rod that was removed".to_string(), modifier: None });
+        assert!(matches!(equip(&mut user, 3, game_data()), Err(InventoryError::Data(_))));
+    }
+}
@@ -0,0 +1,18 @@
+//! The game rules, with no knowledge of Discord.
+//! Every action takes the player's `UserValues` and returns an outcome describing what happened,
+//! which the Discord commands (or any other frontend) only have to render.
+
+pub mod cast;
+pub mod catch;
+pub mod buy;
+pub mod inventory;
+pub mod sell;
+pub mod achievements;
+pub mod contracts;
+pub mod trade;
+pub mod reel;
+pub mod travel;
+pub mod events;
+
+#[cfg(test)]
+pub(crate) mod testing;
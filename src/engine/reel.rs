@@ -0,0 +1,49 @@
+use std::time::Duration;
+use rand::Rng;
+use crate::data::fish::Fish;
+use crate::data::multipliers::MultiplierData;
+use crate::data::rng::reel_rng;
+use crate::engine::cast::Cast;
+
+/// Seconds the player has to hit Reel for a common fish of average weight
+const WINDOW_BASE: f32 = 10.0;
+/// Seconds taken off the window for each rarity tier above Common
+const WINDOW_PER_RARITY: f32 = 1.0;
+/// Seconds taken off the window for every pound over the species' average
+const WINDOW_PER_LB: f32 = 0.01;
+/// No fish ever gives less time than this
+const WINDOW_MIN: f32 = 3.0;
+
+/// How much of the rod's catch chance is lost by clicking at the very end of the window.
+/// Clicking straight away keeps all of it.
+const REACTION_PENALTY: f32 = 0.5;
+
+/// How the player responded to the bite
+pub enum Reel {
+    /// The window ran out before they clicked
+    Missed,
+    Reeled { reaction: Duration },
+}
+
+/// How long the player has to react to this fish biting. Rarer and heavier fish give less time.
+pub fn reel_window(fish: &Fish) -> Duration {
+    let over_average = (fish.weight - fish.fish_type.avg_weight as f32).max(0.0);
+    let seconds = WINDOW_BASE - fish.rarity.ident() as f32 * WINDOW_PER_RARITY - over_average * WINDOW_PER_LB;
+
+    Duration::from_secs_f32(seconds.max(WINDOW_MIN))
+}
+
+/// Decides whether the fish is landed from how quickly the player reeled, replacing the roll made at cast time.
+/// The roll comes from the cast's seed, so the same reaction time always gives the same result.
+pub fn apply_reel(cast: &mut Cast, reel: &Reel, multipliers: &MultiplierData) {
+    let Reel::Reeled { reaction } = reel else {
+        cast.will_catch = false;
+        return;
+    };
+
+    let window = reel_window(&cast.fish);
+    let lateness = (reaction.as_secs_f32() / window.as_secs_f32()).min(1.0);
+    let chance = (cast.rod.get_catch_chance() + multipliers.catch_chance) as f32 * (1.0 - lateness * REACTION_PENALTY);
+
+    cast.will_catch = reel_rng(cast.seed).gen_range(0..1000) <= chance.round() as u32;
+}
@@ -0,0 +1,136 @@
+use std::fmt::Display;
+use crate::data::fish::FishRarity;
+use crate::data::registry::GameData;
+use crate::data::userfile::UserValues;
+
+/// Which fish in the cooler to sell
+pub enum SellFilter {
+    All,
+    Species(String),
+    Rarity(FishRarity),
+}
+
+impl SellFilter {
+    fn matches(&self, species: &str, rarity: &FishRarity) -> bool {
+        match self {
+            SellFilter::All => true,
+            SellFilter::Species(name) => name.eq_ignore_ascii_case(species),
+            SellFilter::Rarity(filter) => filter.ident() == rarity.ident(),
+        }
+    }
+}
+
+pub enum SellError {
+    EmptyCooler,
+    NoMatch,
+}
+
+impl Display for SellError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SellError::EmptyCooler => write!(f, "Your cooler is empty! Go catch some fish with `/fish`."),
+            SellError::NoMatch => write!(f, "You don't have any fish like that in your cooler!"),
+        }
+    }
+}
+
+pub struct FishSale {
+    pub count: usize,
+    pub value: u32,
+    /// Species that are no longer in fish.json, sold for nothing
+    pub stale: Vec<String>,
+}
+
+/// Sells every fish in the cooler matching `filter` at its current freshness.
+/// `now` is a unix timestamp in seconds.
+pub fn sell_fish(user: &mut UserValues, filter: &SellFilter, game_data: &GameData,
+                 now: i64) -> Result<FishSale, SellError> {
+    if user.cooler.is_empty() {
+        return Err(SellError::EmptyCooler);
+    }
+
+    let (sold, kept) = std::mem::take(&mut user.cooler).into_iter()
+        .partition::<Vec<_>, _>(|fish| filter.matches(&fish.species, &fish.rarity));
+    user.cooler = kept;
+
+    if sold.is_empty() {
+        return Err(SellError::NoMatch);
+    }
+
+    let mut sale = FishSale { count: sold.len(), value: 0, stale: Vec::new() };
+    for fish in sold {
+        match fish.value(game_data, now) {
+            Some(value) => sale.value += value,
+            None => sale.stale.push(fish.species),
+        }
+    }
+
+    user.earn(sale.value);
+
+    Ok(sale)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::data::cooler::CooledFish;
+    use crate::data::fish::FishRarity;
+    use crate::data::userfile::UserValues;
+    use crate::engine::testing::{fish, game_data};
+    use super::{sell_fish, SellError, SellFilter};
+
+    fn user() -> UserValues {
+        let mut user = UserValues::default();
+        user.cooler.push(CooledFish::new(&fish("Bass", 10.0), 0));
+        user.cooler.push(CooledFish::new(&fish("Perch", 2.0), 0));
+        user
+    }
+
+    #[test]
+    fn selling_empties_the_matching_fish() {
+        let game_data = game_data();
+        let mut user = user();
+
+        let sale = sell_fish(&mut user, &SellFilter::Species("bass".to_string()), game_data, 0)
+            .ok().expect("the bass should sell");
+        assert_eq!(sale.count, 1);
+        assert_eq!(user.money, sale.value);
+        assert_eq!(user.total_earned, sale.value);
+        assert_eq!(user.cooler.len(), 1);
+        assert_eq!(user.cooler[0].species, "Perch");
+
+        let sale = sell_fish(&mut user, &SellFilter::All, game_data, 0).ok().expect("the perch should sell");
+        assert_eq!(sale.count, 1);
+        assert!(user.cooler.is_empty());
+    }
+
+    #[test]
+    fn fish_lose_value_in_the_cooler() {
+        let game_data = game_data();
+        let fresh = sell_fish(&mut user(), &SellFilter::All, game_data, 0).ok().unwrap();
+        let stale = sell_fish(&mut user(), &SellFilter::All, game_data, 72 * 60 * 60).ok().unwrap();
+        assert!(stale.value < fresh.value);
+    }
+
+    #[test]
+    fn sales_need_matching_fish() {
+        let game_data = game_data();
+        assert!(matches!(sell_fish(&mut UserValues::default(), &SellFilter::All, game_data, 0), Err(SellError::EmptyCooler)));
+
+        let mut user = user();
+        assert!(matches!(sell_fish(&mut user, &SellFilter::Rarity(FishRarity::Mythical), game_data, 0), Err(SellError::NoMatch)));
+        assert_eq!(user.cooler.len(), 2);
+        assert_eq!(user.money, 0);
+    }
+
+    #[test]
+    fn removed_species_sell_for_nothing() {
+        let mut user = UserValues::default();
+        let mut gone = CooledFish::new(&fish("Bass", 10.0), 0);
+        gone.species = "Species that was removed".to_string();
+        user.cooler.push(gone);
+
+        let sale = sell_fish(&mut user, &SellFilter::All, game_data(), 0).ok().unwrap();
+        assert_eq!(sale.value, 0);
+        assert_eq!(sale.stale, vec!["Species that was removed".to_string()]);
+    }
+}
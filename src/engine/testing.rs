@@ -0,0 +1,38 @@
+//! Shared setup for the engine tests, which run against the shipped data files
+
+use std::sync::OnceLock;
+use crate::data::fish::{Fish, FishRarity};
+use crate::data::registry::GameData;
+use crate::data::rods::Rod;
+use crate::engine::cast::Cast;
+
+/// The game data in ./data, loaded once for every test
+pub fn game_data() -> &'static GameData {
+    static GAME_DATA: OnceLock<GameData> = OnceLock::new();
+    GAME_DATA.get_or_init(|| GameData::load().expect("the data files should be valid"))
+}
+
+pub fn rod(name: &str) -> Rod {
+    let base = game_data().rods.get_base_by_name(name).expect("the rod should be in rods.json");
+    Rod { base, modifier: None }
+}
+
+pub fn fish(species: &str, weight: f32) -> Fish {
+    let fish_type = game_data().fish.fish_type_by_name(species).expect("the species should be in fish.json");
+    Fish { fish_type: fish_type.clone(), rarity: FishRarity::Common, weight }
+}
+
+/// A cast that lands `fish` unless something gets in the way
+pub fn cast(rod: Rod, fish: Fish, seed: u64) -> Cast {
+    Cast {
+        fish,
+        rod,
+        will_catch: true,
+        catch_time: 0,
+        seed,
+        override_special: false,
+        bait: None,
+        location: None,
+        conditions: None,
+    }
+}
@@ -0,0 +1,292 @@
+use std::fmt::{Display, Formatter};
+use crate::data::cooler::CooledFish;
+use crate::data::registry::GameData;
+use crate::data::userfile::{OwnedRod, UserValues};
+
+pub enum TradeError {
+    NothingOffered,
+    NoMoney,
+    Casting,
+    MissingFish(String),
+    MissingRod(String),
+    /// The rod is the one its owner is using
+    Equipped(String),
+    CoolerFull,
+    /// The money would take the receiver past what a balance can hold
+    TooRich,
+}
+
+impl Display for TradeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TradeError::NothingOffered => write!(f, "A trade needs something in it!"),
+            TradeError::NoMoney => write!(f, "There isn't enough money for that!"),
+            TradeError::Casting => write!(f, "You can't trade while either of you has a line in the water!"),
+            TradeError::MissingFish(fish) => write!(f, "The {} is no longer in the cooler!", fish),
+            TradeError::MissingRod(rod) => write!(f, "The {} is no longer in the inventory!", rod),
+            TradeError::Equipped(rod) => write!(f, "The {} is equipped! Equip another rod before trading it.", rod),
+            TradeError::CoolerFull => write!(f, "There isn't room in the cooler for those fish!"),
+            TradeError::TooRich => write!(f, "That's more money than their wallet can hold!"),
+        }
+    }
+}
+
+/// What one side of a trade hands over
+#[derive(Debug, Clone, Default)]
+pub struct Offer {
+    pub money: u32,
+    pub fish: Vec<CooledFish>,
+    pub rods: Vec<OwnedRod>,
+}
+
+impl Offer {
+    pub fn is_empty(&self) -> bool {
+        self.money == 0 && self.fish.is_empty() && self.rods.is_empty()
+    }
+
+    /// Removes everything in the offer from the user
+    fn take(&self, user: &mut UserValues) -> Result<(), TradeError> {
+        if user.money < self.money {
+            return Err(TradeError::NoMoney);
+        }
+        user.money -= self.money;
+
+        for fish in &self.fish {
+            let Some(index) = user.cooler.iter().position(|cooled| cooled == fish) else {
+                return Err(TradeError::MissingFish(fish.species.clone()));
+            };
+            user.cooler.remove(index);
+        }
+
+        for rod in &self.rods {
+            let Some(index) = user.rods.iter().position(|owned| owned == rod) else {
+                return Err(TradeError::MissingRod(rod.to_string()));
+            };
+            if index == user.equipped {
+                return Err(TradeError::Equipped(rod.to_string()));
+            }
+            user.rods.remove(index);
+            if index < user.equipped {
+                user.equipped -= 1;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Hands everything in the offer to the user, minus `tax` from the money
+    fn give(&self, user: &mut UserValues, tax: u32) -> Result<(), TradeError> {
+        user.money = user.money.checked_add(self.money - tax).ok_or(TradeError::TooRich)?;
+        user.cooler.extend(self.fish.iter().cloned());
+        user.rods.extend(self.rods.iter().cloned());
+        Ok(())
+    }
+}
+
+impl Display for Offer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut items = Vec::new();
+        if self.money > 0 {
+            items.push(format!("${}", self.money));
+        }
+        for fish in &self.fish {
+            items.push(format!("{} {} ({}lbs)", fish.rarity, fish.species, fish.weight));
+        }
+        for rod in &self.rods {
+            items.push(rod.to_string());
+        }
+
+        if items.is_empty() {
+            write!(f, "Nothing")
+        } else {
+            write!(f, "{}", items.join("\n"))
+        }
+    }
+}
+
+pub struct Payment {
+    /// What the receiver got after tax
+    pub received: u32,
+    pub tax: u32,
+}
+
+/// Sends `amount` from one user to another, taking the transfer tax out of what arrives
+pub fn pay(from: &mut UserValues, to: &mut UserValues, amount: u32, game_data: &GameData) -> Result<Payment, TradeError> {
+    if amount == 0 {
+        return Err(TradeError::NothingOffered);
+    }
+    if from.money < amount {
+        return Err(TradeError::NoMoney);
+    }
+
+    let tax = game_data.multipliers.transfer_tax(amount);
+    let received = to.money.checked_add(amount - tax).ok_or(TradeError::TooRich)?;
+    from.money -= amount;
+    to.money = received;
+
+    Ok(Payment { received: amount - tax, tax })
+}
+
+/// Swaps both offers. Either everything changes hands or nothing does.
+/// Returns the total tax taken from the money on both sides.
+pub fn trade(a: &mut UserValues, a_offer: &Offer, b: &mut UserValues, b_offer: &Offer,
+             game_data: &GameData) -> Result<u32, TradeError> {
+    if a_offer.is_empty() && b_offer.is_empty() {
+        return Err(TradeError::NothingOffered);
+    }
+    if a.cast || b.cast {
+        return Err(TradeError::Casting);
+    }
+
+    // work on copies so a failure halfway through leaves both players untouched
+    let mut new_a = a.clone();
+    let mut new_b = b.clone();
+    a_offer.take(&mut new_a)?;
+    b_offer.take(&mut new_b)?;
+
+    let a_tax = game_data.multipliers.transfer_tax(a_offer.money);
+    let b_tax = game_data.multipliers.transfer_tax(b_offer.money);
+    a_offer.give(&mut new_b, a_tax)?;
+    b_offer.give(&mut new_a, b_tax)?;
+
+    // traded fish have to fit in the receiver's cooler
+    let cooler = &game_data.config.cooler;
+    for (user, incoming) in [(&new_a, &b_offer.fish), (&new_b, &a_offer.fish)] {
+        if incoming.is_empty() {
+            continue;
+        }
+        if let Ok(rod) = user.get_rod(&game_data.rods) {
+            if user.cooler.len() > cooler.capacity(&rod) {
+                return Err(TradeError::CoolerFull);
+            }
+        }
+    }
+
+    *a = new_a;
+    *b = new_b;
+    Ok(a_tax + b_tax)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::data::cooler::CooledFish;
+    use crate::data::userfile::{OwnedRod, UserValues, DEFAULT_ROD};
+    use crate::engine::testing::{fish, game_data, rod};
+    use super::{pay, trade, Offer, TradeError};
+
+    #[test]
+    fn payments_are_taxed() {
+        let game_data = game_data();
+        let (mut from, mut to) = (UserValues { money: 1000, ..UserValues::default() }, UserValues::default());
+
+        let payment = pay(&mut from, &mut to, 1000, game_data).ok().expect("the payment should go through");
+        assert_eq!(payment.tax, game_data.multipliers.transfer_tax(1000));
+        assert_eq!(payment.received + payment.tax, 1000);
+        assert_eq!(from.money, 0);
+        assert_eq!(to.money, payment.received);
+
+        assert!(matches!(pay(&mut from, &mut to, 1, game_data), Err(TradeError::NoMoney)));
+        assert!(matches!(pay(&mut from, &mut to, 0, game_data), Err(TradeError::NothingOffered)));
+    }
+
+    #[test]
+    fn tax_never_takes_more_than_the_payment() {
+        let mut game_data = game_data().clone();
+        game_data.multipliers.transfer_tax = 1.5;
+        let (mut from, mut to) = (UserValues { money: 1000, ..UserValues::default() }, UserValues::default());
+
+        let payment = pay(&mut from, &mut to, 1000, &game_data).ok().expect("the payment should go through");
+        assert_eq!(payment.tax, 1000);
+        assert_eq!(to.money, 0);
+    }
+
+    #[test]
+    fn payments_cant_overflow_the_receiver() {
+        let game_data = game_data();
+        let mut from = UserValues { money: 1000, ..UserValues::default() };
+        let mut to = UserValues { money: u32::MAX - 1, ..UserValues::default() };
+
+        assert!(matches!(pay(&mut from, &mut to, 1000, game_data), Err(TradeError::TooRich)));
+        assert_eq!(from.money, 1000);
+        assert_eq!(to.money, u32::MAX - 1);
+
+        let a_offer = Offer { money: 1000, ..Offer::default() };
+        assert!(matches!(trade(&mut from, &a_offer, &mut to, &Offer::default(), game_data), Err(TradeError::TooRich)));
+        assert_eq!(from.money, 1000);
+        assert_eq!(to.money, u32::MAX - 1);
+
+        // the other way round, the rich side's payment is what overflows
+        let b_offer = Offer { money: 1000, ..Offer::default() };
+        let mut rich = UserValues { money: u32::MAX - 1, ..UserValues::default() };
+        let mut poor = UserValues { money: 1000, ..UserValues::default() };
+        assert!(matches!(trade(&mut rich, &Offer::default(), &mut poor, &b_offer, game_data), Err(TradeError::TooRich)));
+        assert_eq!(rich.money, u32::MAX - 1);
+        assert_eq!(poor.money, 1000);
+    }
+
+    #[test]
+    fn trades_swap_both_offers() {
+        let game_data = game_data();
+        let bass = CooledFish::new(&fish("Bass", 10.0), 0);
+        let spare = OwnedRod::new(&rod("Fiberglass Casting Rod"));
+
+        let mut a = UserValues::default();
+        a.cooler.push(bass.clone());
+        let mut b = UserValues { money: 500, ..UserValues::default() };
+        b.rods.push(spare.clone());
+
+        let a_offer = Offer { fish: vec![bass.clone()], ..Offer::default() };
+        let b_offer = Offer { money: 200, rods: vec![spare.clone()], ..Offer::default() };
+        let tax = trade(&mut a, &a_offer, &mut b, &b_offer, game_data).ok().expect("the trade should go through");
+
+        assert_eq!(tax, game_data.multipliers.transfer_tax(200));
+        assert_eq!(a.money, 200 - tax);
+        assert_eq!(a.rods.last(), Some(&spare));
+        assert!(a.cooler.is_empty());
+        assert_eq!(b.money, 300);
+        assert_eq!(b.cooler, vec![bass]);
+        assert_eq!(b.rods.len(), 1);
+    }
+
+    #[test]
+    fn failed_trades_change_nothing() {
+        let game_data = game_data();
+        let bass = CooledFish::new(&fish("Bass", 10.0), 0);
+
+        let mut a = UserValues { money: 100, ..UserValues::default() };
+        a.cooler.push(bass.clone());
+        let mut b = UserValues { money: 50, ..UserValues::default() };
+
+        // a's side comes out fine, then b can't cover theirs
+        let a_offer = Offer { money: 100, fish: vec![bass.clone()], ..Offer::default() };
+        let b_offer = Offer { money: 500, ..Offer::default() };
+        assert!(matches!(trade(&mut a, &a_offer, &mut b, &b_offer, game_data), Err(TradeError::NoMoney)));
+        assert_eq!(a.money, 100);
+        assert_eq!(a.cooler, vec![bass.clone()]);
+        assert_eq!(b.money, 50);
+        assert!(b.cooler.is_empty());
+
+        // the equipped rod can't be traded away
+        let equipped = a.rods[a.equipped].clone();
+        let a_offer = Offer { rods: vec![equipped.clone()], ..Offer::default() };
+        assert!(matches!(trade(&mut a, &a_offer, &mut b, &Offer::default(), game_data), Err(TradeError::Equipped(_))));
+        assert_eq!(a.rods, vec![equipped]);
+    }
+
+    #[test]
+    fn traded_fish_need_cooler_room() {
+        let game_data = game_data();
+        let bass = CooledFish::new(&fish("Bass", 10.0), 0);
+        let capacity = game_data.config.cooler.capacity(&rod(DEFAULT_ROD));
+
+        let mut a = UserValues::default();
+        a.cooler.push(bass.clone());
+        let cooler = vec![CooledFish::new(&fish("Perch", 2.0), 0); capacity];
+        let mut b = UserValues { cooler, ..UserValues::default() };
+
+        let a_offer = Offer { fish: vec![bass.clone()], ..Offer::default() };
+        assert!(matches!(trade(&mut a, &a_offer, &mut b, &Offer::default(), game_data), Err(TradeError::CoolerFull)));
+        assert_eq!(a.cooler, vec![bass]);
+        assert_eq!(b.cooler.len(), capacity);
+    }
+}
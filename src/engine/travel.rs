@@ -0,0 +1,110 @@
+use std::fmt::{Display, Formatter};
+use crate::data::locations::Location;
+use crate::data::registry::GameData;
+use crate::data::userfile::UserValues;
+
+pub enum TravelError {
+    UnknownLocation(String),
+    AlreadyThere(String),
+    Casting,
+    NoMoney(u32),
+    /// Timestamp the player can travel again at
+    Cooldown(i64),
+    /// The equipped rod can't reach the location's fish
+    TooShallow(Location),
+}
+
+impl Display for TravelError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TravelError::UnknownLocation(name) => write!(f, "There's no place called {}!", name),
+            TravelError::AlreadyThere(name) => write!(f, "You're already at the {}!", name),
+            TravelError::Casting => write!(f, "Reel your line in before you travel!"),
+            TravelError::NoMoney(cost) => write!(f, "You need ${} to travel there!", cost),
+            TravelError::Cooldown(ready_at) => write!(f, "You're still tired from your last trip! You can travel again <t:{}:R>.", ready_at),
+            TravelError::TooShallow(location) => write!(f, "Your rod can't reach the fish at the {}! You need one that fishes at least {}ft deep.",
+                                                        location, location.min_depth),
+        }
+    }
+}
+
+/// Moves the player to the named location, charging its travel cost
+pub fn travel(user: &mut UserValues, name: &str, game_data: &GameData, now: i64) -> Result<Location, TravelError> {
+    let locations = &game_data.locations;
+
+    let Some(location) = locations.get_by_name(name) else {
+        return Err(TravelError::UnknownLocation(name.to_string()));
+    };
+
+    if locations.current(user.location.as_deref()).name == location.name {
+        return Err(TravelError::AlreadyThere(location.name.clone()));
+    }
+
+    if user.cast {
+        return Err(TravelError::Casting);
+    }
+
+    let ready_at = user.traveled_at + locations.travel_cooldown as i64 * 60;
+    if now < ready_at {
+        return Err(TravelError::Cooldown(ready_at));
+    }
+
+    // a rod that no longer exists can't cast anywhere, so it doesn't stop anyone travelling
+    if let Ok(rod) = user.get_rod(&game_data.rods) {
+        if !location.can_fish(rod.get_depth()) {
+            return Err(TravelError::TooShallow(location.clone()));
+        }
+    }
+
+    if user.money < location.travel_cost {
+        return Err(TravelError::NoMoney(location.travel_cost));
+    }
+
+    user.money -= location.travel_cost;
+    user.location = Some(location.name.clone());
+    user.traveled_at = now;
+
+    Ok(location.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::data::userfile::UserValues;
+    use crate::engine::testing::game_data;
+    use super::{travel, TravelError};
+
+    const NOW: i64 = 1_790_000_000;
+
+    #[test]
+    fn travelling_charges_the_fare() {
+        let game_data = game_data();
+        let river = game_data.locations.get_by_name("River").unwrap();
+        let mut user = UserValues { money: river.travel_cost + 10, ..UserValues::default() };
+
+        let location = travel(&mut user, "river", game_data, NOW).ok().expect("the trip should go ahead");
+        assert_eq!(location.name, "River");
+        assert_eq!(user.location.as_deref(), Some("River"));
+        assert_eq!(user.money, 10);
+        assert_eq!(user.traveled_at, NOW);
+    }
+
+    #[test]
+    fn trips_are_checked() {
+        let game_data = game_data();
+        let home = game_data.locations.home().name.clone();
+        let mut user = UserValues::default();
+
+        assert!(matches!(travel(&mut user, "Atlantis", game_data, NOW), Err(TravelError::UnknownLocation(_))));
+        assert!(matches!(travel(&mut user, &home, game_data, NOW), Err(TravelError::AlreadyThere(_))));
+        assert!(matches!(travel(&mut user, "Deep Trench", game_data, NOW), Err(TravelError::TooShallow(_))));
+        assert!(matches!(travel(&mut user, "Open Ocean", game_data, NOW), Err(TravelError::NoMoney(_))));
+
+        user.cast = true;
+        assert!(matches!(travel(&mut user, "River", game_data, NOW), Err(TravelError::Casting)));
+
+        let mut user = UserValues { money: 1000, traveled_at: NOW - 60, ..UserValues::default() };
+        assert!(matches!(travel(&mut user, "River", game_data, NOW), Err(TravelError::Cooldown(_))));
+        assert_eq!(user.money, 1000);
+        assert_eq!(user.location, None);
+    }
+}
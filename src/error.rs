@@ -0,0 +1,77 @@
+use std::fmt::{Display, Formatter};
+
+/// Everything that can go wrong while loading data or running a command.
+/// `Display` is the detailed message for the logs, `user_message` is what the player sees.
+#[derive(Debug)]
+pub enum AnglerError {
+    /// A file couldn't be read or written
+    Io { path: String, source: std::io::Error },
+    /// A data file isn't valid JSON for its type
+    Parse { path: String, source: serde_json::Error },
+    /// The SQLite user store couldn't be opened or set up
+    Database { path: String, source: rusqlite::Error },
+    /// The game data loaded but failed validation
+    InvalidData(Vec<String>),
+    /// A fish species that isn't in fish.json
+    UnknownFish(String),
+    /// A rod that isn't in rods.json
+    UnknownRod(String),
+    /// A rod modifier that isn't in rods.json
+    UnknownModifier(String),
+    /// A bait that isn't in bait.json
+    UnknownBait(String),
+    /// A command option that's missing or out of range
+    InvalidOption(String),
+    /// Discord rejected a request
+    Discord(Box<serenity::Error>),
+}
+
+impl AnglerError {
+    /// A friendly explanation for the player, without internal details
+    pub fn user_message(&self) -> String {
+        match self {
+            AnglerError::Io { .. } | AnglerError::Parse { .. } | AnglerError::Database { .. } | AnglerError::InvalidData(_) =>
+                "The tackle box is jammed! Please try again later.".to_string(),
+            AnglerError::UnknownFish(name) =>
+                format!("Nobody has seen a **{}** around here in a while. Please let an admin know!", name),
+            AnglerError::UnknownRod(name) | AnglerError::UnknownModifier(name) | AnglerError::UnknownBait(name) =>
+                format!("The **{}** seems to have washed away. Please let an admin know!", name),
+            AnglerError::InvalidOption(reason) => reason.clone(),
+            AnglerError::Discord(_) => "Something went wrong talking to Discord, please try again!".to_string(),
+        }
+    }
+
+    /// Reads and parses a JSON data file
+    pub fn load_json<T: serde::de::DeserializeOwned>(path: &str) -> Result<T, AnglerError> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|source| AnglerError::Io { path: path.to_string(), source })?;
+
+        serde_json::from_str(contents.as_str())
+            .map_err(|source| AnglerError::Parse { path: path.to_string(), source })
+    }
+}
+
+impl Display for AnglerError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AnglerError::Io { path, source } => write!(f, "Failed to access {}: {}", path, source),
+            AnglerError::Parse { path, source } => write!(f, "Failed to parse {}: {}", path, source),
+            AnglerError::Database { path, source } => write!(f, "Failed to open the database {}: {}", path, source),
+            AnglerError::InvalidData(errors) => write!(f, "Invalid game data:\n{}", errors.join("\n")),
+            AnglerError::UnknownFish(name) => write!(f, "Unknown fish \"{}\"", name),
+            AnglerError::UnknownRod(name) => write!(f, "Unknown rod \"{}\"", name),
+            AnglerError::UnknownModifier(name) => write!(f, "Unknown rod modifier \"{}\"", name),
+            AnglerError::UnknownBait(name) => write!(f, "Unknown bait \"{}\"", name),
+            AnglerError::InvalidOption(reason) => write!(f, "Invalid option: {}", reason),
+            AnglerError::Discord(e) => write!(f, "Discord error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for AnglerError {}
+
+impl From<serenity::Error> for AnglerError {
+    fn from(e: serenity::Error) -> Self {
+        AnglerError::Discord(Box::new(e))
+    }
+}
@@ -0,0 +1,4 @@
+pub mod logging;
+pub mod data;
+pub mod engine;
+pub mod error;
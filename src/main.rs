@@ -1,22 +1,27 @@
-use std::{env, sync::Arc};
+use std::{env, sync::{Arc, RwLock}};
 
 use serenity::{all::{GatewayIntents, Message, ResumedEvent, Ready, Interaction, CommandInteraction},
  Client, async_trait, client::{EventHandler, Context}, prelude::TypeMapKey, gateway::ShardManager,
                builder::{CreateInteractionResponse, CreateInteractionResponseMessage}};
-use serenity::all::{ActivityData, Command, CreateCommand, OnlineStatus};
-use crate::commands::fish::{catch, FishCatch};
-use crate::data::fish::{Fish, FishData, FishRarity};
-use crate::data::userfile::set_userfile_casting_false;
-
-pub mod logging;
-pub mod data;
+use serenity::all::{ActivityData, Command, CreateCommand, GuildId, OnlineStatus};
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+use crate::commands::fish::{catch, restore_casts};
+use crate::commands::tournament::restore_tournaments;
+use crate::data::casts::pending_casts;
+use crate::data::fish::{Fish, FishRarity};
+use crate::data::registry::{game_data, GameData, GameDataContainer};
+use crate::data::rng::new_seed;
+use crate::data::rods::Rod;
+use crate::data::userfile::{modify_user, store, UserValues};
+use crate::engine::cast::{roll_cast, Cast};
+use crate::engine::catch::{resolve_catch, CatchOutcome};
+use crate::error::AnglerError;
+
+pub use angler_bot::{data, engine, error, logging, hey, nay, say, wow, yay};
 
 mod commands;
 
-// todo: ideas
-//   - Leveling system
-//   - A bait system to increase the chances of catching fish
-
 pub const SKEPZ_ID: u64 = 318884828508454912;
 
 pub struct ShardManagerContainer;
@@ -25,18 +30,22 @@ impl TypeMapKey for ShardManagerContainer {
     type Value = Arc<ShardManager>;
 }
 
+/// Set once the casts from before a restart have been picked up, since `ready` fires on every reconnect
+static CASTS_RESTORED: AtomicBool = AtomicBool::new(false);
+
+/// Clears the cast flag for anyone whose cast isn't waiting in the pending casts file,
+/// so nobody gets stuck unable to fish after a crash
 pub fn stop_users_fishing() {
-    // loop through all the files in /data/users
-    let guild_paths = std::fs::read_dir("./data/guilds").unwrap();
-    for path in guild_paths {
-        // get all guild directories
-        let path = path.unwrap().path();
-        let path = path.to_str().unwrap();
-        let user_paths = std::fs::read_dir(format!("{}/users/", path)).unwrap();
-        for user_path in user_paths {
-            let user_path = user_path.unwrap().path();
-            let user_path = user_path.to_str().unwrap();
-            set_userfile_casting_false(user_path.to_string());
+    let pending = pending_casts().into_iter()
+        .map(|pending| (pending.guild_id, pending.user_id))
+        .collect::<HashSet<(u64, u64)>>();
+
+    let store = store();
+    for guild_id in store.guilds() {
+        for (id, user_values) in store.users(guild_id) {
+            if user_values.cast && !pending.contains(&(guild_id, id.get())) {
+                modify_user(guild_id, &id, |user| user.cast = false);
+            }
         }
     }
 }
@@ -49,12 +58,146 @@ pub async fn command_response<S: Into<String>>(ctx: &Context, command: &CommandI
     }
 }
 
+/// Sends the text a command produced, passing its error on to the dispatcher
+async fn text_response(ctx: &Context, command: &CommandInteraction,
+                       content: Result<String, AnglerError>) -> Result<(), AnglerError> {
+    let data = CreateInteractionResponseMessage::new().content(content?);
+    command.create_response(&ctx.http, CreateInteractionResponse::Message(data)).await?;
+    Ok(())
+}
+
+/// Tells the player their command failed, privately, without any internal details
+async fn error_response(ctx: &Context, command: &CommandInteraction, error: &AnglerError) {
+    let data = CreateInteractionResponseMessage::new()
+        .content(error.user_message())
+        .ephemeral(true);
+    if let Err(err) = command.create_response(&ctx.http, CreateInteractionResponse::Message(data)).await {
+        nay!("Failed to respond to command: {}", err)
+    }
+}
+
 pub async fn register_command(ctx: &Context, cmd: CreateCommand) {
     if let Err(e) = Command::create_global_command(&ctx.http, cmd).await {
         nay!("Failed to register a command: {}", e);
     }
 }
 
+/// Registers the commands whose choices come from the game data, so they can be refreshed after a reload
+pub async fn register_data_commands(ctx: &Context) {
+    let game_data = game_data(ctx).await;
+    register_command(ctx, commands::fish::register(game_data.config.motd.clone(), &game_data.bait)).await;
+    register_command(ctx, commands::admin::register(&game_data.events)).await;
+    register_command(ctx, commands::travel::register(&game_data.locations)).await;
+}
+
+/// Catches a chosen fish straight away: !spawn <rarity> <weight> <fish>
+async fn spawn(ctx: &Context, msg: &Message, guild_id: GuildId) -> Result<(), AnglerError> {
+    let args = msg.content.split(' ').collect::<Vec<&str>>();
+    if args.len() < 4 {
+        return Err(AnglerError::InvalidOption("Usage: !spawn <rarity> <weight> <fish>".to_string()));
+    }
+    let rarity = FishRarity::from_string(args[1])
+        .ok_or_else(|| AnglerError::InvalidOption(format!("Unknown rarity: {}", args[1])))?;
+    // get args[2] as an f32
+    let weight = args[2].parse::<f32>()
+        .map_err(|_| AnglerError::InvalidOption(format!("Invalid weight: {}", args[2])))?;
+    // get the fish
+    let game_data = game_data(ctx).await;
+    let fish_name = args[3..].join(" ");
+    let fish = game_data.fish.fish_type_by_name(fish_name.as_str())
+        .ok_or(AnglerError::UnknownFish(fish_name))?;
+
+    let fish = Fish {
+        fish_type: fish.clone(),
+        rarity,
+        weight,
+    };
+
+    let user = store().read(&msg.author.id, guild_id.get());
+    let rod = user.get_rod(&game_data.rods)?;
+    catch(ctx.http.clone(), msg.channel_id, msg.author.id, guild_id, Cast {
+        rod,
+        fish,
+        will_catch: true,
+        catch_time: 0,
+        seed: new_seed(),
+        override_special: true,
+        bait: None,
+        location: user.location,
+        conditions: None,
+    }, game_data.clone(), false).await;
+
+    Ok(())
+}
+
+/// Re-rolls a logged cast without touching anyone's profile: !replay <seed> [rod] [| bait] [@ location/weather/time/date].
+/// The `@` part is logged after the seed of every cast. Without it the cast is rolled where the sender is,
+/// in the guild's current weather and time of day.
+async fn replay(ctx: &Context, msg: &Message, guild_id: GuildId) -> Result<(), AnglerError> {
+    const USAGE: &str = "Usage: !replay <seed> [rod] [| bait] [@ location/weather/time/date]";
+
+    let (content, logged) = match msg.content.split_once('@') {
+        Some((content, logged)) => (content.trim(), Some(logged.trim())),
+        None => (msg.content.as_str(), None),
+    };
+    let (content, bait_name) = match content.split_once('|') {
+        Some((content, bait)) => (content.trim(), Some(bait.trim())),
+        None => (content, None),
+    };
+    let args = content.split(' ').collect::<Vec<&str>>();
+    let Some(Ok(seed)) = args.get(1).map(|seed| seed.parse::<u64>()) else {
+        return Err(AnglerError::InvalidOption(USAGE.to_string()));
+    };
+    let game_data = game_data(ctx).await;
+
+    let bait = match bait_name {
+        Some(name) => Some(game_data.bait.get_by_name(name).ok_or_else(|| AnglerError::UnknownBait(name.to_string()))?),
+        None => None,
+    };
+
+    let user = store().read(&msg.author.id, guild_id.get());
+    let (location, conditions) = match logged {
+        Some(logged) => {
+            let (location, conditions) = logged.split_once('/')
+                .ok_or_else(|| AnglerError::InvalidOption(USAGE.to_string()))?;
+            let location = game_data.locations.get_by_name(location.trim())
+                .ok_or_else(|| AnglerError::InvalidOption(format!("Unknown location {}", location.trim())))?;
+            let conditions = game_data.weather.parse_conditions(conditions)
+                .ok_or_else(|| AnglerError::InvalidOption(format!("Unknown conditions {}", conditions.trim())))?;
+            (location, conditions)
+        }
+        None => (game_data.locations.current(user.location.as_deref()), game_data.weather.current(guild_id.get())),
+    };
+
+    let rod = if args.len() > 2 {
+        let rod_name = args[2..].join(" ");
+        let rod_data = &game_data.rods;
+        let all_rods = rod_data.rods.iter().flat_map(|base| {
+            std::iter::once(None).chain(rod_data.modifiers.iter().map(Some))
+                .map(move |modifier| Rod { base: base.clone(), modifier: modifier.cloned() })
+        }).collect::<Vec<Rod>>();
+        all_rods.into_iter().find(|rod| rod.to_string() == rod_name)
+            .ok_or(AnglerError::UnknownRod(rod_name))?
+    } else {
+        user.get_rod(&game_data.rods)?
+    };
+
+    let cast = roll_cast(&rod, bait, location, &conditions, &game_data, seed);
+    let events = game_data.events.enabled(&store().read_guild(guild_id.get()).events);
+    let outcome = match resolve_catch(&mut UserValues::default(), &cast, &game_data, &events, 0) {
+        CatchOutcome::Caught { value, .. } => format!("caught for ${}", value),
+        CatchOutcome::CoolerFull { .. } => "caught, but the cooler was full".to_string(),
+        CatchOutcome::Escaped { .. } => "escaped".to_string(),
+        CatchOutcome::LineBroke { .. } => "broke the line".to_string(),
+        CatchOutcome::Stolen { event, .. } => format!("stolen by {}", event.name),
+    };
+
+    let reply = format!("Seed {} with a {} at the {} ({}, {}): {}lb **{}** in {} seconds, {}",
+                        seed, rod, location, conditions, conditions.date, cast.fish.weight, cast.fish, cast.catch_time / 1000, outcome);
+    msg.reply(&ctx.http, reply).await?;
+    Ok(())
+}
+
 struct Handler;
 
 #[async_trait]
@@ -69,46 +212,51 @@ impl EventHandler for Handler {
             return;
         }
 
-        if msg.content.starts_with("!spawn") {
-            let args = msg.content.split(' ').collect::<Vec<&str>>();
-            if args.len() < 4 {
-                return;
-            }
-            let rarity = FishRarity::from_string(args[1]).unwrap();
-            // get args[2] as an f32
-            let weight = args[2].parse::<f32>().unwrap();
-            // get the fish
-            let fishdata = FishData::load();
-            let fish = fishdata.fish_type_by_name(args[3..].join(" ")).unwrap();
-
-            let fish = Fish {
-                fish_type: fish.clone(),
-                rarity: rarity.clone(),
-                weight,
-            };
+        let Some(guild_id) = msg.guild_id else {
+            return;
+        };
 
-            catch(ctx.http, msg.channel_id, msg.author.id, msg.guild_id.unwrap(), FishCatch {
-                user_file: data::userfile::read_userfile(&msg.author.id, msg.guild_id.unwrap().get()),
-                fish,
-                will_catch: true,
-                override_special: true,
-            }).await;
+        let result = if msg.content.starts_with("!spawn") {
+            spawn(&ctx, &msg, guild_id).await
+        } else if msg.content.starts_with("!replay") {
+            replay(&ctx, &msg, guild_id).await
+        } else {
+            Ok(())
+        };
 
+        if let Err(e) = result {
+            nay!("{} failed: {}", msg.content, e);
+            if let Err(e) = msg.reply(&ctx.http, e.to_string()).await {
+                nay!("Failed to send message: {}", e);
+            }
         }
     }
 
     async fn ready(&self, ctx: Context, ready: Ready) {
-        let config = data::config::Config::load();
+        let config = game_data(&ctx).await.config.clone();
         // remove all global commands
         //Command::set_global_commands(&ctx.http, Vec::new()).await.expect("Failed to remove global commands");
         // create the commands
-        register_command(&ctx, commands::fish::register(config.motd.clone())).await;
+        register_data_commands(&ctx).await;
         register_command(&ctx, commands::shop::register()).await;
         register_command(&ctx, commands::buy::register()).await;
         register_command(&ctx, commands::balance::register()).await;
         register_command(&ctx, commands::info::register()).await;
         register_command(&ctx, commands::rod_info::register()).await;
         register_command(&ctx, commands::bestiary::register()).await;
+        register_command(&ctx, commands::inventory::register()).await;
+        register_command(&ctx, commands::equip::register()).await;
+        register_command(&ctx, commands::sellrod::register()).await;
+        register_command(&ctx, commands::sell::register()).await;
+        register_command(&ctx, commands::cooler::register()).await;
+        register_command(&ctx, commands::leaderboard::register()).await;
+        register_command(&ctx, commands::records::register()).await;
+        register_command(&ctx, commands::achievements::register()).await;
+        register_command(&ctx, commands::contracts::register()).await;
+        register_command(&ctx, commands::tournament::register()).await;
+        register_command(&ctx, commands::pay::register()).await;
+        register_command(&ctx, commands::trade::register()).await;
+        register_command(&ctx, commands::forecast::register()).await;
 
         yay!("{} is connected! {}", ready.user.name, config.motd);
         if !config.debug_mode {
@@ -116,15 +264,41 @@ impl EventHandler for Handler {
         } else {
             ctx.set_presence(Some(ActivityData::custom("Under development")), OnlineStatus::Online);
         }
+
+        if !CASTS_RESTORED.swap(true, Ordering::SeqCst) {
+            stop_users_fishing();
+            restore_casts(&ctx, game_data(&ctx).await).await;
+            restore_tournaments(ctx.http.clone()).await;
+        }
     }
 
-    async fn resume(&self, _: Context, _: ResumedEvent) {
-        let config = data::config::Config::load();
-        stop_users_fishing();
+    async fn resume(&self, ctx: Context, _: ResumedEvent) {
+        let config = game_data(&ctx).await.config.clone();
         yay!("{}", config.motd);
     }
 
     async fn interaction_create(&self, ctx: Context, interaction: Interaction) {
+        if let Interaction::Component(component) = &interaction {
+            let result = if commands::leaderboard::is_leaderboard_button(component) {
+                commands::leaderboard::turn_page(&ctx, component).await
+            } else if commands::records::is_records_button(component) {
+                commands::records::turn_page(&ctx, component).await
+            } else {
+                Ok(())
+            };
+
+            if let Err(e) = result {
+                nay!("Button {} failed for {}: {}", component.data.custom_id, component.user.name, e);
+                if !matches!(e, AnglerError::Discord(_)) {
+                    let data = CreateInteractionResponseMessage::new().content(e.user_message()).ephemeral(true);
+                    if let Err(err) = component.create_response(&ctx.http, CreateInteractionResponse::Message(data)).await {
+                        nay!("Failed to respond to button: {}", err)
+                    }
+                }
+            }
+            return;
+        }
+
         if let Interaction::Command(command) = interaction {
             let command_name = command.data.name.as_str();
             let sender = &command.user;
@@ -137,37 +311,90 @@ impl EventHandler for Handler {
             let command_options = &command.data.options();
             let channel = &command.channel_id;
 
-            let config = data::config::Config::load();
+            let game_data = game_data(&ctx).await;
 
-            if config.debug_mode && sender.id != SKEPZ_ID {
+            if game_data.config.debug_mode && sender.id != SKEPZ_ID {
                 command_response(&ctx, &command, "The pond is being restocked, please try again later! (Under maintenance)").await;
                 return;
             }
 
-            match command_name {
+            let result = match command_name {
                 "fish" => {
-                    command_response(&ctx, &command, commands::fish::run(&ctx, channel, sender, &guild_id)).await
+                    text_response(&ctx, &command, commands::fish::run(command_options, &ctx, channel, sender, &guild_id, game_data.clone())).await
                 }
                 "shop" => {
-                    commands::shop::run(&ctx, &command).await
+                    commands::shop::run(&ctx, &command, &game_data).await
                 }
                 "buy" => {
-                    command_response(&ctx, &command, commands::buy::run(command_options, sender, &guild_id)).await
+                    text_response(&ctx, &command, commands::buy::run(command_options, sender, &guild_id, &game_data)).await
                 }
                 "rod" => {
-                    commands::rod_info::run(command_options, &ctx, &command).await;
+                    commands::rod_info::run(command_options, &ctx, &command, &game_data).await
                 }
                 "balance" => {
-                    command_response(&ctx, &command, commands::balance::run(&sender.id, &guild_id)).await
+                    text_response(&ctx, &command, Ok(commands::balance::run(&sender.id, &guild_id))).await
                 }
                 "info" => {
-                    commands::info::run(&ctx, &command, sender, &guild_id).await;
+                    commands::info::run(&ctx, &command, sender, &guild_id, &game_data).await
                 }
                 "bestiary" => {
-                    commands::bestiary::run(&ctx, &command, sender, &guild_id).await;
+                    commands::bestiary::run(&ctx, &command, sender, &guild_id, &game_data).await
+                }
+                "inventory" => {
+                    commands::inventory::run(&ctx, &command, sender, &guild_id, &game_data).await
+                }
+                "equip" => {
+                    text_response(&ctx, &command, Ok(commands::equip::run(command_options, sender, &guild_id, &game_data))).await
+                }
+                "sellrod" => {
+                    text_response(&ctx, &command, commands::sellrod::run(command_options, sender, &guild_id, &game_data)).await
+                }
+                "sell" => {
+                    text_response(&ctx, &command, commands::sell::run(command_options, sender, &guild_id, &game_data)).await
+                }
+                "cooler" => {
+                    commands::cooler::run(&ctx, &command, sender, &guild_id, &game_data).await
+                }
+                "leaderboard" => {
+                    commands::leaderboard::run(command_options, &ctx, &command, &guild_id).await
+                }
+                "records" => {
+                    commands::records::run(command_options, &ctx, &command, sender, &guild_id, &game_data).await
+                }
+                "achievements" => {
+                    commands::achievements::run(&ctx, &command, sender, &guild_id, &game_data).await
+                }
+                "contracts" => {
+                    commands::contracts::run(command_options, &ctx, &command, sender, &guild_id, &game_data).await
+                }
+                "tournament" => {
+                    commands::tournament::run(&ctx, &command, &guild_id).await
+                }
+                "pay" => {
+                    text_response(&ctx, &command, Ok(commands::pay::run(command_options, sender, &guild_id, &game_data))).await
+                }
+                "trade" => {
+                    commands::trade::run(command_options, &ctx, &command, sender, &guild_id, &game_data).await
+                }
+                "forecast" => {
+                    commands::forecast::run(&ctx, &command, &guild_id, &game_data).await
+                }
+                "travel" => {
+                    text_response(&ctx, &command, Ok(commands::travel::run(command_options, sender, &guild_id, &game_data))).await
+                }
+                "admin" => {
+                    commands::admin::run(command_options, &ctx, &command).await
                 }
                 _ => {
-                    command_response(&ctx, &command, "Unknown command").await
+                    text_response(&ctx, &command, Ok("Unknown command".to_string())).await
+                }
+            };
+
+            if let Err(e) = result {
+                nay!("/{} failed for {} in guild {}: {}", command_name, sender.name, guild_id, e);
+                // if Discord itself failed there's no point trying to reply
+                if !matches!(e, AnglerError::Discord(_)) {
+                    error_response(&ctx, &command, &e).await;
                 }
             }
         }
@@ -185,6 +412,14 @@ async fn main() {
         return;
     };
 
+    let game_data = match GameData::load() {
+        Ok(game_data) => game_data,
+        Err(e) => {
+            nay!("Failed to load game data: {}", e);
+            return;
+        }
+    };
+
     let intents = GatewayIntents::GUILD_MESSAGES
         | GatewayIntents::DIRECT_MESSAGES
         | GatewayIntents::MESSAGE_CONTENT;
@@ -198,6 +433,7 @@ async fn main() {
     {
         let mut data = client.data.write().await;
         data.insert::<ShardManagerContainer>(client.shard_manager.clone());
+        data.insert::<GameDataContainer>(Arc::new(RwLock::new(Arc::new(game_data))));
     }
 
     let shard_manager = client.shard_manager.clone();
@@ -208,7 +444,7 @@ async fn main() {
             return;
         }
 
-        // loop through all the files in /data/users
+        // pending casts stay on disk and are restored on the next startup
         stop_users_fishing();
 
         shard_manager.shutdown_all().await;